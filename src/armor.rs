@@ -9,44 +9,35 @@ pub enum ArmorType {
 pub struct Armor {
     armor_type: ArmorType,
     health: u32,
+    elven: bool, // Elven armor never wears out
 }
 
 impl Armor {
     /// Create a new armor
     pub fn new(a: ArmorType) -> Armor {
-        let health = match a {
-            ArmorType::None => 0,    // 0 * 7
-            ArmorType::Leather => 7, // 1 * 7
-            ArmorType::Chainmail => 2 * 7,
-            ArmorType::Plate => 3 * 7,
-        };
-
         Armor {
             armor_type: a,
-            health,
+            health: Armor::max_health_by_type(a),
+            elven: false,
         }
     }
 
-    /// Return the cost in GP of some armor in a given context
-    pub fn cost(a: ArmorType, is_vendor: bool) -> u32 {
-        let value;
-
-        if is_vendor {
-            value = match a {
-                ArmorType::None => 0,
-                ArmorType::Leather => 1250,
-                ArmorType::Chainmail => 1500,
-                ArmorType::Plate => 2000,
-            }
-        } else if a == ArmorType::None {
-            value = 0;
-        } else {
-            let id = Armor::get_id(a);
-
-            value = id * 10;
+    /// Create a new elven armor
+    pub fn elven(a: ArmorType) -> Armor {
+        Armor {
+            elven: true,
+            ..Armor::new(a)
         }
+    }
 
-        value
+    /// Return the health of a new armor of a given type
+    fn max_health_by_type(a: ArmorType) -> u32 {
+        match a {
+            ArmorType::None => 0,    // 0 * 7
+            ArmorType::Leather => 7, // 1 * 7
+            ArmorType::Chainmail => 2 * 7,
+            ArmorType::Plate => 3 * 7,
+        }
     }
 
     /// Convert an armor type to its internal ID
@@ -83,6 +74,10 @@ impl Armor {
     ///
     /// Return true if the armor is destroyed
     pub fn damage(&mut self, damage: u32) -> bool {
+        if self.elven {
+            return false;
+        }
+
         if damage > self.health {
             self.health = 0;
             return true;
@@ -92,4 +87,31 @@ impl Armor {
 
         false
     }
+
+    /// True if this is elven armor
+    pub fn is_elven(&self) -> bool {
+        self.elven
+    }
+
+    /// Return how much wear the armor has taken
+    pub fn wear(&self) -> u32 {
+        Armor::max_health_by_type(self.armor_type) - self.health
+    }
+
+    /// Return the condition of the armor as a percentage, or None if it
+    /// doesn't wear
+    pub fn condition(&self) -> Option<u32> {
+        let max_health = Armor::max_health_by_type(self.armor_type);
+
+        if max_health == 0 || self.elven {
+            return None;
+        }
+
+        Some(self.health * 100 / max_health)
+    }
+
+    /// Restore the armor to new
+    pub fn repair(&mut self) {
+        self.health = Armor::max_health_by_type(self.armor_type);
+    }
 }
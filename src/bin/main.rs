@@ -1,65 +1,72 @@
+use std::collections::VecDeque;
+use std::env;
 use std::io::{stdin, stdout, Write};
+use std::process;
+use std::time::Duration;
 
 use rand::rngs::ThreadRng;
 use rand::rng;
 use rand::Rng;
 
-use wizardscastle::armor::{Armor, ArmorType};
+use wizardscastle::armor::ArmorType;
+use wizardscastle::config::GameConfig;
+use wizardscastle::curse::{CurseTable, CurseType};
+use wizardscastle::difficulty::Difficulty;
+use wizardscastle::economy::{Economy, Price};
+use wizardscastle::equipment::Equipment;
 use wizardscastle::error::Error;
 use wizardscastle::game::{
-    BookEvent, ChestEvent, DrinkEvent, GameState, HitResult, OrbEvent, RandomMessage,
+    BookEvent, ChestEvent, DrinkEvent, GameState, HitResult, OrbEvent, RandomMessage, SpellEvent,
+    UseEvent,
 };
-use wizardscastle::game::{CombatEvent, Direction, Event, Game, Stairs};
-use wizardscastle::monster::MonsterType;
+use wizardscastle::game::{Bribe, CombatEvent, Direction, Event, Game, HaggleEvent, Omen, Stairs};
+use wizardscastle::inventory::{Item, Stack};
+use wizardscastle::monster::{MonsterTable, MonsterType, SpawnMode, WoundLevel};
+use wizardscastle::path::PathStep;
 use wizardscastle::player::{Gender, Race, Stat};
-use wizardscastle::room::RoomType;
+use wizardscastle::ring::RingType;
+use wizardscastle::ruleset::Ruleset;
+use wizardscastle::shield::ShieldType;
+use wizardscastle::speedrun::{Speedrun, Split};
+use wizardscastle::spell::SpellType;
+use wizardscastle::stock::Ware;
 use wizardscastle::treasure::TreasureType;
-use wizardscastle::weapon::{Weapon, WeaponType};
+use wizardscastle::view::RememberedRoom;
+use wizardscastle::weapon::WeaponType;
 
 struct UI {
     game: Game,
     rng: ThreadRng,
+    travel: VecDeque<PathStep>,
+    explore_steps: u32,
+    explore_limit: u32,
+    ambushed: bool,
+    speedrun: Option<Speedrun>,
 }
 
 impl UI {
+    /// Return a random monster type
+    fn rand_monster_type(&mut self) -> MonsterType {
+        let monster = self.game.monster_defs();
+
+        let i = self.rng.random_range(0..monster.len());
+
+        monster[i].monster_type
+    }
+
     /// Return a random monster name
     fn rand_monster_str(&mut self) -> String {
-        let monster = [
-            MonsterType::Kobold,
-            MonsterType::Orc,
-            MonsterType::Wolf,
-            MonsterType::Goblin,
-            MonsterType::Ogre,
-            MonsterType::Troll,
-            MonsterType::Bear,
-            MonsterType::Minotaur,
-            MonsterType::Gargoyle,
-            MonsterType::Chimera,
-            MonsterType::Balrog,
-            MonsterType::Dragon,
-        ];
+        let m = self.rand_monster_type();
 
-        let i = self.rng.random_range(0..monster.len());
+        self.monster_name(m)
+    }
 
-        UI::monster_name(monster[i])
+    fn monster_name(&self, m: MonsterType) -> String {
+        self.game.monster_def(m).name.to_uppercase()
     }
 
-    fn monster_name(m: MonsterType) -> String {
-        match m {
-            MonsterType::Kobold => String::from("KOBOLD"),
-            MonsterType::Orc => String::from("ORC"),
-            MonsterType::Wolf => String::from("WOLF"),
-            MonsterType::Goblin => String::from("GOBLIN"),
-            MonsterType::Ogre => String::from("OGRE"),
-            MonsterType::Troll => String::from("TROLL"),
-            MonsterType::Bear => String::from("BEAR"),
-            MonsterType::Minotaur => String::from("MINOTAUR"),
-            MonsterType::Gargoyle => String::from("GARGOYLE"),
-            MonsterType::Chimera => String::from("CHIMERA"),
-            MonsterType::Balrog => String::from("BALROG"),
-            MonsterType::Dragon => String::from("DRAGON"),
-            MonsterType::Vendor => String::from("VENDOR"),
-        }
+    fn monster_article(&self, m: MonsterType) -> String {
+        self.game.monster_def(m).article.to_uppercase()
     }
 
     fn stat_name(s: Stat) -> String {
@@ -88,6 +95,126 @@ impl UI {
         }
     }
 
+    fn shield_name(s: ShieldType) -> String {
+        match s {
+            ShieldType::None => String::from("NO SHIELD"),
+            ShieldType::Buckler => String::from("BUCKLER"),
+            ShieldType::Kite => String::from("KITE SHIELD"),
+            ShieldType::Tower => String::from("TOWER SHIELD"),
+        }
+    }
+
+    fn ring_name(r: RingType) -> String {
+        match r {
+            RingType::None => String::from("NO RING"),
+            RingType::Protection => String::from("RING OF PROTECTION"),
+            RingType::Accuracy => String::from("RING OF ACCURACY"),
+            RingType::Regeneration => String::from("RING OF REGENERATION"),
+        }
+    }
+
+    fn equipment_name(e: Equipment) -> String {
+        match e {
+            Equipment::Shield(s) => UI::shield_name(s),
+            Equipment::Ring(r) => UI::ring_name(r),
+            Equipment::EnchantedWeapon(w, bonus) => {
+                format!("+{} {}", bonus, UI::weapon_name(w))
+            }
+            Equipment::ElvenArmor(a) => format!("ELVEN {}", UI::armor_name(a)),
+        }
+    }
+
+    /// Return the name of something a vendor sells
+    fn ware_name(w: Ware) -> String {
+        match w {
+            Ware::Armor(a) => format!("{} ARMOR", UI::armor_name(a)),
+            Ware::Weapon(w) => format!("A {}", UI::weapon_name(w)),
+            Ware::Equipment(e) => UI::equipment_name(e),
+            Ware::Potion(Stat::Intelligence) => String::from("AN INTELLIGENCE POTION"),
+            Ware::Potion(s) => format!("A {} POTION", UI::stat_name(s)),
+            Ware::Lamp => String::from("A LAMP"),
+            Ware::Supply(i) => UI::item_name(i),
+        }
+    }
+
+    /// Return the name of the player's weapon, with any enchantment
+    fn player_weapon_name(&self) -> String {
+        let w_name = UI::weapon_name(self.game.player_weapon_type());
+
+        match self.game.player_weapon_bonus() {
+            0 => w_name,
+            bonus => format!("+{} {}", bonus, w_name),
+        }
+    }
+
+    /// Return the name of the player's armor, noting elven armor
+    fn player_armor_name(&self) -> String {
+        let a_name = UI::armor_name(self.game.player_armor_type());
+
+        if self.game.player_armor_is_elven() {
+            format!("ELVEN {}", a_name)
+        } else {
+            a_name
+        }
+    }
+
+    fn item_name(i: Item) -> String {
+        match i {
+            Item::Lamp => String::from("A LAMP"),
+            Item::Flare => String::from("FLARES"),
+            Item::Treasure(t) => UI::treasure_name(t),
+            Item::OrbOfZot => String::from("THE ORB OF ZOT"),
+            Item::Runestaff => String::from("THE RUNESTAFF"),
+            Item::HealingPotion => String::from("A HEALING POTION"),
+            Item::SightElixir => String::from("AN ELIXIR OF SIGHT"),
+            Item::MappingScroll => String::from("A SCROLL OF MAPPING"),
+            Item::Solvent => String::from("A FLASK OF SOLVENT"),
+        }
+    }
+
+    /// Return the name of a stack of items, with the count if it stacks
+    fn stack_name(s: Stack) -> String {
+        match (s.item, s.count) {
+            (Item::Flare, _) => format!("{} FLARES", s.count),
+            (_, 1) => UI::item_name(s.item),
+            (Item::HealingPotion, n) => format!("{} HEALING POTIONS", n),
+            (Item::SightElixir, n) => format!("{} ELIXIRS OF SIGHT", n),
+            (Item::MappingScroll, n) => format!("{} SCROLLS OF MAPPING", n),
+            (Item::Solvent, n) => format!("{} FLASKS OF SOLVENT", n),
+            (item, _) => UI::item_name(item),
+        }
+    }
+
+    /// Return the name of some number of a ware
+    fn wares_name(w: Ware, count: u32) -> String {
+        match (w, count) {
+            (Ware::Supply(item), _) => UI::stack_name(Stack { item, count }),
+            (_, 1) => UI::ware_name(w),
+            (_, n) => format!("{} X {}", n, UI::ware_name(w)),
+        }
+    }
+
+    fn curse_name(c: CurseType) -> String {
+        match c {
+            CurseType::None => String::from("NOTHING"),
+            CurseType::Forgetfulness => String::from("FORGETFULNESS"),
+            CurseType::TheLeech => String::from("THE LEECH"),
+            CurseType::Lethargy => String::from("LETHARGY"),
+            CurseType::Clumsiness => String::from("CLUMSINESS"),
+            CurseType::Greed => String::from("GREED"),
+            CurseType::Darkness => String::from("DARKNESS"),
+        }
+    }
+
+    fn difficulty_name(d: Difficulty) -> String {
+        match d {
+            Difficulty::Easy => String::from("EASY"),
+            Difficulty::Normal => String::from("NORMAL"),
+            Difficulty::Hard => String::from("HARD"),
+            Difficulty::Nightmare => String::from("NIGHTMARE"),
+        }
+    }
+
     fn stair_name(s: Stairs) -> String {
         match s {
             Stairs::Up => String::from("UP"),
@@ -95,6 +222,28 @@ impl UI {
         }
     }
 
+    fn spell_name(s: SpellType) -> String {
+        match s {
+            SpellType::Web => String::from("WEB"),
+            SpellType::Fireball => String::from("FIREBALL"),
+            SpellType::Deathspell => String::from("DEATHSPELL"),
+            SpellType::Heal => String::from("HEAL"),
+            SpellType::Light => String::from("LIGHT"),
+            SpellType::DetectMonsters => String::from("DETECT MONSTERS"),
+            SpellType::Blink => String::from("BLINK"),
+            SpellType::RemoveCurse => String::from("REMOVE CURSE"),
+        }
+    }
+
+    fn dir_name(d: Direction) -> String {
+        match d {
+            Direction::North => String::from("NORTH"),
+            Direction::South => String::from("SOUTH"),
+            Direction::West => String::from("WEST"),
+            Direction::East => String::from("EAST"),
+        }
+    }
+
     fn treasure_name(t: TreasureType) -> String {
         match t {
             TreasureType::RubyRed => String::from("THE RUBY RED"),
@@ -108,25 +257,24 @@ impl UI {
         }
     }
 
-    fn room_name(r: &RoomType) -> String {
+    fn room_name(&self, r: RememberedRoom) -> String {
         match r {
-            RoomType::Empty => String::from("AN EMPTY ROOM"),
-            RoomType::Entrance => String::from("THE ENTRANCE"),
-            RoomType::StairsDown => String::from("STAIRS GOING DOWN"),
-            RoomType::StairsUp => String::from("STAIRS GOING UP"),
-            RoomType::Gold => String::from("GOLD PIECES"),
-            RoomType::Pool => String::from("A POOL"),
-            RoomType::Chest => String::from("A CHEST"),
-            RoomType::Flares => String::from("FLARES"),
-            RoomType::Warp(_) => String::from("A WARP"),
-            RoomType::Sinkhole => String::from("A SINKHOLE"),
-            RoomType::CrystalOrb => String::from("A CRYSTAL ORB"),
-            RoomType::Book => String::from("A BOOK"),
-            RoomType::Monster(m) => {
-                let mon_str = UI::monster_name(m.monster_type());
-                format!("{} {}", UI::get_article(&mon_str), mon_str)
-            }
-            RoomType::Treasure(t) => UI::treasure_name(*t.treasure_type()),
+            RememberedRoom::Empty => String::from("AN EMPTY ROOM"),
+            RememberedRoom::Entrance => String::from("THE ENTRANCE"),
+            RememberedRoom::StairsDown => String::from("STAIRS GOING DOWN"),
+            RememberedRoom::StairsUp => String::from("STAIRS GOING UP"),
+            RememberedRoom::Gold => String::from("GOLD PIECES"),
+            RememberedRoom::Pool => String::from("A POOL"),
+            RememberedRoom::Chest => String::from("A CHEST"),
+            RememberedRoom::Flares => String::from("FLARES"),
+            RememberedRoom::Warp => String::from("A WARP"),
+            RememberedRoom::Sinkhole => String::from("A SINKHOLE"),
+            RememberedRoom::CrystalOrb => String::from("A CRYSTAL ORB"),
+            RememberedRoom::Book => String::from("A BOOK"),
+            RememberedRoom::Monster(m) => {
+                format!("{} {}", self.monster_article(m), self.monster_name(m))
+            }
+            RememberedRoom::Treasure(t) => UI::treasure_name(t),
         }
     }
 
@@ -137,22 +285,6 @@ impl UI {
         }
     }
 
-    fn starts_with_vowel(s: &str) -> bool {
-        if let Some(c) = String::from(s).to_uppercase().chars().next() {
-            return c == 'A' || c == 'E' || c == 'I' || c == 'O' || c == 'U';
-        }
-
-        false
-    }
-
-    fn get_article(s: &str) -> String {
-        if UI::starts_with_vowel(s) {
-            return String::from("AN");
-        }
-
-        String::from("A")
-    }
-
     /// Move a direction
     fn move_dir(&mut self, dir: Direction) {
         self.game.move_dir(dir);
@@ -206,8 +338,17 @@ impl UI {
             return false;
         }
 
-        let x = UI::input_coord("X-COORD (1 = FAR WEST  8 = FAR EAST )? ");
-        let y = UI::input_coord("Y-COORD (1 = FAR NORTH 8 = FAR SOUTH)? ");
+        let (x, y) = if self.game.config().rules.x_east_west {
+            (
+                UI::input_coord("X-COORD (1 = FAR WEST  8 = FAR EAST )? "),
+                UI::input_coord("Y-COORD (1 = FAR NORTH 8 = FAR SOUTH)? "),
+            )
+        } else {
+            let row = UI::input_coord("X-COORD (1 = FAR NORTH 8 = FAR SOUTH)? ");
+            let column = UI::input_coord("Y-COORD (1 = FAR WEST  8 = FAR EAST )? ");
+
+            (column, row)
+        };
         let z = UI::input_coord("Z-COORD (1 = TOP       8 = BOTTOM   )? ");
 
         match self.game.teleport(x - 1, y - 1, z - 1) {
@@ -225,6 +366,166 @@ impl UI {
         true
     }
 
+    /// Parse "X,Y,Z" into 1-based coordinates
+    fn parse_coords(s: &str) -> Option<(u32, u32, u32)> {
+        let nums: Vec<u32> = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|n| !n.is_empty())
+            .map(|n| n.parse::<u32>())
+            .collect::<Result<_, _>>()
+            .ok()?;
+
+        match nums[..] {
+            [x, y, z] if x >= 1 && y >= 1 && z >= 1 => Some((x, y, z)),
+            _ => None,
+        }
+    }
+
+    /// Plan a trip to a location or to the nearest stairs
+    ///
+    /// Returns true if there's somewhere to go
+    fn travel(&mut self, dest: &str) -> bool {
+        if self.game.player_is_blind() {
+            println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str());
+            return false;
+        }
+
+        let path = if dest == "STAIRS" {
+            self.game.path_to_stairs()
+        } else {
+            match UI::parse_coords(dest) {
+                Some((a, b, z)) => {
+                    let (x, y) = self.order_coords(a, b);
+
+                    self.game.path_to(x - 1, y - 1, z - 1)
+                }
+                None => {
+                    println!("** TRY GO X,Y,Z OR GO STAIRS");
+                    return false;
+                }
+            }
+        };
+
+        match path {
+            Ok(p) if p.is_empty() => {
+                println!("** YOU'RE ALREADY THERE");
+                false
+            }
+            Ok(p) => {
+                self.travel = VecDeque::from(p);
+                true
+            }
+            Err(Error::NoPath) => {
+                println!("** YOU DON'T KNOW A SAFE WAY THERE");
+                false
+            }
+            Err(Error::OutOfBounds) => {
+                println!("** THAT'S NOT IN THE CASTLE, {}", self.race_str());
+                false
+            }
+            Err(err) => panic!("{:#?}", err),
+        }
+    }
+
+    /// True if the player is partway through a trip
+    fn traveling(&self) -> bool {
+        !self.travel.is_empty()
+    }
+
+    /// Take a single step of a trip or exploration
+    ///
+    /// Returns false if the step couldn't be taken
+    fn walk(&mut self, step: PathStep) -> bool {
+        match step {
+            PathStep::Move(dir) => println!("YOU GO {}", UI::dir_name(dir)),
+            PathStep::Stairs(stairs) => println!("YOU GO {} THE STAIRS", UI::stair_name(stairs)),
+        }
+
+        if self.game.take_step(step).is_err() {
+            println!("\n** YOUR WAY IS BLOCKED");
+            return false;
+        }
+
+        self.game.discover_room_at_player();
+
+        true
+    }
+
+    /// Take the next step of a trip
+    fn travel_step(&mut self) {
+        if let Some(step) = self.travel.pop_front() {
+            if !self.walk(step) {
+                self.travel.clear();
+            }
+        }
+    }
+
+    /// Start exploring, optionally changing the step limit
+    ///
+    /// Returns true if exploration started
+    fn explore(&mut self, limit: &str) -> bool {
+        if !limit.is_empty() {
+            match limit.parse::<u32>() {
+                Ok(n) if n > 0 => self.explore_limit = n,
+                _ => {
+                    println!("** TRY X OR X FOLLOWED BY A NUMBER OF STEPS");
+                    return false;
+                }
+            }
+        }
+
+        self.explore_steps = self.explore_limit;
+
+        true
+    }
+
+    /// True if the player is exploring
+    fn exploring(&self) -> bool {
+        self.explore_steps > 0
+    }
+
+    /// Stop any trip or exploration in progress
+    fn stop_walking(&mut self) {
+        self.travel.clear();
+        self.explore_steps = 0;
+    }
+
+    /// Take the next step toward the nearest undiscovered room
+    ///
+    /// Returns true if a step was taken
+    fn explore_step(&mut self) -> bool {
+        self.explore_steps -= 1;
+
+        let step = match self.game.path_to_unexplored() {
+            Ok(path) => match path.first() {
+                Some(step) => *step,
+                None => {
+                    // Forgetfulness can wipe out the room the player is in
+                    self.game.discover_room_at_player();
+                    return true;
+                }
+            },
+            Err(Error::NoPath) => {
+                println!("** THERE'S NOTHING LEFT YOU CAN SAFELY EXPLORE");
+                self.stop_walking();
+                return false;
+            }
+            Err(Error::Blind) => {
+                println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str());
+                self.stop_walking();
+                return false;
+            }
+            Err(err) => panic!("{:#?}", err),
+        };
+
+        if !self.walk(step) {
+            self.stop_walking();
+            return false;
+        }
+
+        true
+    }
+
     /// Drink
     fn drink(&mut self) {
         let s = match self.game.drink() {
@@ -264,52 +565,51 @@ impl UI {
         };
 
         println!("YOU TAKE A DRINK AND {}", s);
+
+        self.report_lifted_curses();
     }
 
     /// Get the printable character for a room
-    fn room_char(room_type: &RoomType) -> char {
+    fn room_char(room_type: RememberedRoom) -> char {
         match room_type {
-            RoomType::Empty => '.',
-            RoomType::Entrance => 'E',
-            RoomType::StairsDown => 'D',
-            RoomType::StairsUp => 'U',
-            RoomType::Gold => 'G',
-            RoomType::Pool => 'P',
-            RoomType::Chest => 'C',
-            RoomType::Flares => 'F',
-            RoomType::Warp(_) => 'W',
-            RoomType::Sinkhole => 'S',
-            RoomType::CrystalOrb => 'O',
-            RoomType::Book => 'B',
-            RoomType::Monster(ref m) => {
-                if m.monster_type() == MonsterType::Vendor {
-                    'V'
-                } else {
-                    'M'
-                }
-            }
-            RoomType::Treasure(_) => 'T',
+            RememberedRoom::Empty => '.',
+            RememberedRoom::Entrance => 'E',
+            RememberedRoom::StairsDown => 'D',
+            RememberedRoom::StairsUp => 'U',
+            RememberedRoom::Gold => 'G',
+            RememberedRoom::Pool => 'P',
+            RememberedRoom::Chest => 'C',
+            RememberedRoom::Flares => 'F',
+            RememberedRoom::Warp => 'W',
+            RememberedRoom::Sinkhole => 'S',
+            RememberedRoom::CrystalOrb => 'O',
+            RememberedRoom::Book => 'B',
+            RememberedRoom::Monster(MonsterType::Vendor) => 'V',
+            RememberedRoom::Monster(_) => 'M',
+            RememberedRoom::Treasure(_) => 'T',
         }
     }
 
     /// Print a map
-    fn map(&mut self, show_all: bool) {
+    fn map(&mut self) {
         if self.game.player_is_blind() {
             println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str());
             return;
         }
 
-        let z = self.game.player_z();
+        let view = self.game.player_view();
 
-        for y in 0..self.game.dungeon_ysize() {
-            for x in 0..self.game.dungeon_xsize() {
+        let (px, py, z) = view.player_location();
+
+        for y in 0..view.ysize() {
+            for x in 0..view.xsize() {
                 if x >= 1 {
                     print!("   ");
                 }
 
-                let r = self.game.dungeon_room_at(x, y, z);
+                let room_type = view.room_at(x, y, z);
 
-                let bracket = x == self.game.player_x() && y == self.game.player_y();
+                let bracket = x == px && y == py;
 
                 if bracket {
                     print!("<");
@@ -317,10 +617,9 @@ impl UI {
                     print!(" ");
                 }
 
-                if r.discovered || show_all {
-                    print!("{}", UI::room_char(&r.roomtype));
-                } else {
-                    print!("?");
+                match room_type {
+                    Some(room_type) => print!("{}", UI::room_char(room_type)),
+                    None => print!("?"),
                 }
 
                 if bracket {
@@ -360,15 +659,20 @@ impl UI {
     /// Print intro text
     ///
     /// Note: the original version lacked this preamble--it only appears in the
-    /// magazine article. It was, however, included in the MBASIC port.
+    /// magazine article. It was, however, included in the MBASIC port. Classic
+    /// rules show only the title.
     ///
-    fn intro() {
+    fn intro(story: bool) {
         println!("\n{:*^64}\n", "");
 
         println!("{:^64}\n", "* * * THE WIZARD'S CASTLE * * *");
 
         println!("{:*^64}\n", "");
 
+        if !story {
+            return;
+        }
+
         println!(
             "{:^64}",
             "MANY CYCLES AGO, IN THE KINGDOM OF N'DIC, THE GNOMIC"
@@ -497,7 +801,14 @@ impl UI {
 
         println!("HERE IS A LIST OF ARMOR YOU CAN BUY (WITH COST IN <>)\n");
 
-        println!("PLATE<30> CHAINMAIL<20> LEATHER<10> NOTHING<0>");
+        let economy = &self.game.config().economy;
+
+        println!(
+            "PLATE<{}> CHAINMAIL<{}> LEATHER<{}> NOTHING<0>",
+            economy.armor_cost(ArmorType::Plate, false),
+            economy.armor_cost(ArmorType::Chainmail, false),
+            economy.armor_cost(ArmorType::Leather, false)
+        );
 
         let _ = loop {
             let armor_str = UI::get_input(Some("\nYOUR CHOICE? "));
@@ -508,8 +819,9 @@ impl UI {
                 Some("L") => break self.game.player_purchase_armor(ArmorType::Leather, false),
                 Some("N") => break self.game.player_purchase_armor(ArmorType::None, false),
                 _ => {
-                    let mon_str = self.rand_monster_str();
-                    let article = UI::get_article(&mon_str);
+                    let m = self.rand_monster_type();
+                    let article = self.monster_article(m);
+                    let mon_str = self.monster_name(m);
 
                     println!(
                         "\n** ARE YOU A {} OR {} {}? TYPE P,C,L OR N",
@@ -532,7 +844,14 @@ impl UI {
 
         println!("HERE IS A LIST OF WEAPONS YOU CAN BUY (WITH COST IN <>)\n");
 
-        println!("SWORD<30> MACE<20> DAGGER<10> NOTHING<0>");
+        let economy = &self.game.config().economy;
+
+        println!(
+            "SWORD<{}> MACE<{}> DAGGER<{}> NOTHING<0>",
+            economy.weapon_cost(WeaponType::Sword, false),
+            economy.weapon_cost(WeaponType::Mace, false),
+            economy.weapon_cost(WeaponType::Dagger, false)
+        );
 
         let _ = loop {
             let armor_str = UI::get_input(Some("\nYOUR CHOICE? "));
@@ -556,8 +875,13 @@ impl UI {
             return;
         }
 
+        let prompt = format!(
+            "\nWANT TO BUY A LAMP FOR {} GP's? ",
+            self.game.config().economy.get(Price::StartLamp)
+        );
+
         let _ = loop {
-            let lamp_str = UI::get_input(Some("\nWANT TO BUY A LAMP FOR 20 GP's? "));
+            let lamp_str = UI::get_input(Some(&prompt));
 
             match lamp_str.get(..1) {
                 Some("Y") => break self.game.player_purchase_lamp(true),
@@ -581,8 +905,13 @@ impl UI {
             self.game.player_gp()
         );
 
+        let prompt = match self.game.config().economy.get(Price::Flare) {
+            1 => String::from("FLARES COST 1 GP EACH, HOW MANY DO YOU WANT? "),
+            cost => format!("FLARES COST {} GP's EACH, HOW MANY DO YOU WANT? ", cost),
+        };
+
         loop {
-            let flare_str = UI::get_input(Some("FLARES COST 1 GP EACH, HOW MANY DO YOU WANT? "));
+            let flare_str = UI::get_input(Some(&prompt));
 
             let flare_count = match flare_str.parse::<u32>() {
                 Ok(f) => f,
@@ -602,27 +931,39 @@ impl UI {
         }
     }
 
-    /// Print the player's location
+    /// Put a pair of coordinates in the order the rules give them
     ///
-    /// Note: the original game had a horizontal Y axis and a vertical X axis.
-    /// This version reverses that.
+    /// Note: the original game had a horizontal Y axis and a vertical X axis,
+    /// so the row came first. Modern rules reverse that. Swapping is its own
+    /// inverse, so this works going into the game and coming out of it.
     ///
+    fn order_coords(&self, a: u32, b: u32) -> (u32, u32) {
+        if self.game.config().rules.x_east_west {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Print the player's location
     fn print_location(&self) {
         if self.game.player_is_blind() {
             return;
         }
 
+        let (a, b) = self.order_coords(self.game.player_x(), self.game.player_y());
+
         println!(
             "YOU ARE AT ({},{}) LEVEL {}",
-            self.game.player_x() + 1,
-            self.game.player_y() + 1,
+            a + 1,
+            b + 1,
             self.game.player_z() + 1
         );
     }
 
     /// Print player stats
     fn print_stats(&self) {
-        println!(
+        print!(
             "ST={} IQ={} DX={} FLARES={} GP's={}",
             self.game.player_stat(Stat::Strength),
             self.game.player_stat(Stat::Intelligence),
@@ -631,10 +972,31 @@ impl UI {
             self.game.player_gp()
         );
 
-        let w_name = UI::weapon_name(self.game.player_weapon_type());
-        let a_name = UI::armor_name(self.game.player_armor_type());
+        if let Some(limit) = self.game.turn_limit() {
+            print!(" TURN={}/{}", self.game.turn(), limit);
+        }
+
+        if let Some(speedrun) = &self.speedrun {
+            print!(" TIME={}", UI::time_str(speedrun.elapsed()));
+        }
+
+        println!();
+
+        print!(
+            "{}{} / {}{}",
+            self.player_weapon_name(),
+            UI::condition_str(self.game.player_weapon_condition()),
+            self.player_armor_name(),
+            UI::condition_str(self.game.player_armor_condition())
+        );
+
+        if self.game.player_shield_type() != ShieldType::None {
+            print!(" / {}", UI::shield_name(self.game.player_shield_type()));
+        }
 
-        print!("{} / {}", w_name, a_name);
+        if self.game.player_ring_type() != RingType::None {
+            print!(" / {}", UI::ring_name(self.game.player_ring_type()));
+        }
 
         if self.game.player_has_lamp() {
             print!(" / A LAMP");
@@ -643,13 +1005,121 @@ impl UI {
         println!("\n");
     }
 
+    /// Return a time as minutes, seconds, and tenths
+    fn time_str(time: Duration) -> String {
+        let tenths = time.as_millis() / 100;
+
+        format!("{}:{:02}.{}", tenths / 600, tenths / 10 % 60, tenths % 10)
+    }
+
+    fn split_name(s: Split) -> String {
+        match s {
+            Split::Runestaff => String::from("THE RUNESTAFF"),
+            Split::OrbOfZot => String::from("THE ORB OF ZOT"),
+            Split::Exit => String::from("THE EXIT"),
+        }
+    }
+
+    /// Record speedrun splits for any milestones just reached
+    fn check_splits(&mut self) {
+        let reached = [
+            (Split::Runestaff, self.game.player_has_runestaff()),
+            (Split::OrbOfZot, self.game.player_has_orb_of_zot()),
+            (Split::Exit, self.game.state() == GameState::Exit),
+        ];
+
+        let Some(speedrun) = &mut self.speedrun else {
+            return;
+        };
+
+        for (split, done) in reached {
+            if !done {
+                continue;
+            }
+
+            if let Some(time) = speedrun.split(split) {
+                println!(
+                    "\n** SPLIT: {} AT {}",
+                    UI::split_name(split),
+                    UI::time_str(time)
+                );
+            }
+        }
+    }
+
+    /// Return the condition of a piece of gear for display
+    fn condition_str(condition: Option<u32>) -> String {
+        match condition {
+            Some(percent) => format!(" ({}%)", percent),
+            None => String::new(),
+        }
+    }
+
     /// Print the current room
     fn print_room(&mut self) {
-        let room = self.game.room_at_player();
-
-        let room_str = UI::room_name(&room.roomtype);
+        let room_str = self.room_name(self.game.player_view().here());
 
         println!("HERE YOU FIND {}", room_str);
+
+        let items = self.game.room_items();
+
+        if !items.is_empty() {
+            let names: Vec<String> = items.iter().map(|s| UI::stack_name(*s)).collect();
+
+            println!("\nON THE FLOOR: {}", names.join(", "));
+        }
+
+        if let Some(omen) = self.game.omen() {
+            println!("\nTHE NORN STONE WHISPERS: {}", UI::omen_str(omen));
+        }
+
+        if let Some(condition) = self.monster_condition() {
+            println!("\n{}", condition);
+        }
+    }
+
+    /// Describe what the Norn Stone foretells
+    fn omen_str(omen: Omen) -> &'static str {
+        match omen {
+            Omen::ChestExplodes => "THIS CHEST WILL EXPLODE",
+            Omen::ChestGas => "THIS CHEST IS FULL OF GAS",
+            Omen::ChestGold => "THIS CHEST HOLDS GOLD",
+            Omen::ChestEquipment => "THIS CHEST HOLDS GEAR",
+            Omen::Drink(DrinkEvent::Stronger) => "THIS POOL WILL MAKE YOU STRONGER",
+            Omen::Drink(DrinkEvent::Weaker) => "THIS POOL WILL MAKE YOU WEAKER",
+            Omen::Drink(DrinkEvent::Smarter) => "THIS POOL WILL MAKE YOU SMARTER",
+            Omen::Drink(DrinkEvent::Dumber) => "THIS POOL WILL MAKE YOU DUMBER",
+            Omen::Drink(DrinkEvent::Nimbler) => "THIS POOL WILL MAKE YOU NIMBLER",
+            Omen::Drink(DrinkEvent::Clumsier) => "THIS POOL WILL MAKE YOU CLUMSIER",
+            Omen::Drink(DrinkEvent::ChangeRace) => "THIS POOL WILL CHANGE YOUR RACE",
+            Omen::Drink(DrinkEvent::ChangeGender) => "THIS POOL WILL CHANGE YOUR GENDER",
+            Omen::GazeHarms => "THIS ORB WILL DO YOU HARM",
+            Omen::GazeVision => "THIS ORB WILL SHOW YOU A DISTANT ROOM",
+            Omen::GazeNothing => "THIS ORB WILL SHOW YOU NOTHING OF USE",
+        }
+    }
+
+    /// Describe how the monster in the player's room is doing, if it's hurt
+    /// or stuck and the player can see it
+    fn monster_condition(&self) -> Option<String> {
+        let sighting = self.game.player_view().monster_sighting()?;
+
+        let wounds = match sighting.wound_level {
+            WoundLevel::Unhurt => None,
+            WoundLevel::Scratched => Some("IT HAS A FEW SCRATCHES"),
+            WoundLevel::Wounded => Some("IT IS WOUNDED"),
+            WoundLevel::BadlyWounded => Some("IT IS BADLY WOUNDED"),
+            WoundLevel::NearDeath => Some("IT IS NEARLY DEAD"),
+        };
+
+        let webbed = sighting.webbed;
+
+        match (wounds, webbed) {
+            (None, false) => None,
+            (None, true) => Some(String::from("IT IS STUCK IN A WEB")),
+            (Some(w), false) => Some(String::from(w)),
+            (Some(w), true) => Some(format!("{} AND STUCK IN A WEB", w)),
+        }
     }
 
     /// Print messaging when monster defeated by melee or magic
@@ -668,16 +1138,33 @@ impl UI {
             }
 
             if result.killed_vendor {
-                println!("\nYOU GET ALL HIS WARES\n");
-                println!("PLATE ARMOR");
-                println!("A SWORD");
-                println!("A STRENGTH POTION");
-                println!("AN INTELLIGENCE POTION");
-                println!("A DEXTERITY POTION");
+                let loot = self.game.take_vendor_loot();
+
+                if loot.is_empty() && !result.got_lamp {
+                    println!("\nHE HAD NOTHING YOU COULD USE");
+                } else {
+                    println!("\nYOU GET ALL HIS WARES\n");
+                }
+
+                for (ware, count) in loot {
+                    println!("{}", UI::wares_name(ware, count));
+
+                    if let Ware::Supply(item) = ware {
+                        if self.left_on_floor(item) {
+                            println!("  (YOUR PACK IS FULL, SO THESE STAY ON THE FLOOR)");
+                        }
+                    }
+                }
 
                 if result.got_lamp {
                     println!("A LAMP");
+
+                    if self.left_on_floor(Item::Lamp) {
+                        println!("\nBUT YOUR PACK IS FULL, SO THE LAMP STAYS ON THE FLOOR");
+                    }
                 }
+
+                println!("\nAND HIS PURSE OF {} GP's", result.treasure);
             } else {
                 if result.got_runestaff {
                     println!("\nGREAT ZOT! YOU'VE FOUND THE RUNESTAFF");
@@ -713,6 +1200,17 @@ impl UI {
                 self.monster_defeated_message(result, m_art, m_name);
             }
 
+            Ok(CombatEvent::HitResisted(result)) => {
+                println!("\n  YOU HIT THE LOUSY {}", m_name);
+                println!("\n  BUT IT SHRUGS OFF HALF THE BLOW");
+
+                if result.broke_weapon {
+                    println!("\nOH NO! YOUR {} BROKE", UI::weapon_name(weapon_type));
+                }
+
+                self.monster_defeated_message(result, m_art, m_name);
+            }
+
             Ok(CombatEvent::Miss) => {
                 println!("\n  DRAT! MISSED");
             }
@@ -750,6 +1248,36 @@ impl UI {
                 println!("\n  HAH! HE MISSED YOU");
             }
 
+            Ok(CombatEvent::MonsterFire(_damage, _defeated)) => {
+                println!("\nTHE {} BREATHES FIRE", m_name);
+
+                println!("\n  YEOW! YOUR ARMOR IS NO HELP AT ALL");
+            }
+
+            Ok(CombatEvent::MonsterRegenerates(_hp)) => {
+                println!("\nTHE {}'S WOUNDS CLOSE BEFORE YOUR EYES", m_name);
+            }
+
+            Ok(CombatEvent::MonsterFear(_defeated)) => {
+                println!("\nTHE {} FILLS YOU WITH TERROR", m_name);
+
+                println!("\n  YOU FEEL YOUR WITS SLIPPING AWAY");
+            }
+
+            Ok(CombatEvent::MonsterCallsPack) => {
+                println!("\nTHE {} HOWLS, AND SOMETHING NEARBY HOWLS BACK", m_name);
+            }
+
+            Ok(CombatEvent::MonsterMultiAttack(count)) => {
+                println!("\nTHE {} ATTACKS {} TIMES AT ONCE", m_name, count);
+            }
+
+            Ok(CombatEvent::MonsterBlocked) => {
+                println!("\nTHE {} ATTACKS", m_name);
+
+                println!("\n  CLANG! YOUR SHIELD TURNS THE BLOW");
+            }
+
             Ok(any) => panic!("unexpected event while being attacked {:#?}", any),
 
             Err(err) => panic!("error in combat being attacked {:#?}", err),
@@ -801,94 +1329,171 @@ impl UI {
 
     /// Handle Bribe
     fn combat_bribe(&mut self) -> bool {
-        let mut bribed = false;
+        let gp = self.game.player_gp();
+        let treasures = self.game.player_get_treasures();
 
-        match self.game.bribe_proposition() {
-            Ok(Some(t_type)) => loop {
-                let tname = UI::treasure_name(t_type);
+        if gp == 0 && treasures.is_empty() {
+            println!("\n'ALL I WANT IS YOUR LIFE!'");
 
-                let yn = UI::get_input(Some(&format!(
-                    "\nI WANT {}, WILL YOU GIVE IT TO ME? ",
-                    tname
-                )));
+            if let Err(err) = self.game.bribe_decline() {
+                panic!("no bribe: {:#?}", err);
+            }
 
-                match yn.get(..1) {
-                    Some("Y") => {
-                        match self.game.bribe_accept() {
-                            Ok(_) => {
-                                bribed = true;
-                            }
-                            Err(err) => {
-                                panic!("agree to bribe: {:#?}", err);
-                            }
-                        };
-                        break;
-                    }
-                    Some("N") => {
-                        match self.game.bribe_decline() {
-                            Ok(_) => {
-                                bribed = false;
-                            }
-                            Err(err) => {
-                                panic!("disagree to bribe: {:#?}", err);
-                            }
-                        };
-                        break;
-                    }
-                    _ => println!("\n** ANSWER YES OR NO"),
-                }
+            return false;
+        }
+
+        let bribe = match UI::choose_gp_or_treasure(gp, &treasures, "\nWHAT WILL YOU OFFER? ") {
+            Some(Some(t)) => Bribe::Treasure(t),
+            Some(None) => match UI::choose_gp(gp) {
+                0 => return false,
+                amount => Bribe::Gold(amount),
             },
-            Ok(None) => {
-                println!("\n'ALL I WANT IS YOUR LIFE!'");
-            }
-            Err(err) => {
-                panic!("bribe proposition: {:#?}", err);
-            }
+            None => return false,
         };
 
-        bribed
+        match self.game.bribe_offer(bribe) {
+            Ok(true) => true,
+            Ok(false) => {
+                println!("\n'YOU INSULT ME WITH THAT?'");
+                false
+            }
+            Err(err) => panic!("bribe offer: {:#?}", err),
+        }
     }
 
     /// Handle combat spells
     fn combat_spell(&mut self, m_art: &str, m_name: &str) {
-        match UI::get_input(Some("\nWHICH SPELL (WEB, FIREBALL, OR DEATHSPELL)? ")).get(..1) {
-            Some("W") => match self.game.spell_web() {
-                Ok(CombatEvent::Hit(_)) => (),
-                Ok(CombatEvent::Died) => (),
-                Ok(any) => panic!("Unexpected: {:#?}", any),
-                Err(err) => panic!("{:#?}", err),
-            },
-            Some("F") => match self.game.spell_fireball() {
-                Ok(CombatEvent::Hit(hr)) => {
+        let spells = self.game.available_spells(true);
+
+        let prompt = format!("\nWHICH SPELL ({})? ", UI::spell_list(&spells));
+
+        let spell = match UI::choose_spell(&spells, &UI::get_input(Some(&prompt))) {
+            Some(s) => s,
+            None => {
+                println!("\n** TRY ONE OF THE OPTIONS GIVEN");
+                return;
+            }
+        };
+
+        if spell == SpellType::Deathspell {
+            print!("\nDEATH - - - ");
+        }
+
+        match self.game.cast_in_combat(spell) {
+            Ok(CombatEvent::Hit(hr)) => match spell {
+                SpellType::Fireball => {
                     println!("\n  IT DOES {} POINTS OF DAMAGE.\n", hr.damage);
                     self.monster_defeated_message(hr, m_art, m_name);
                 }
-                Ok(CombatEvent::Died) => (),
-                Ok(any) => panic!("Unexpected: {:#?}", any),
-                Err(err) => panic!("{:#?}", err),
+                SpellType::Deathspell => {
+                    println!("HIS\n");
+                    self.monster_defeated_message(hr, m_art, m_name);
+                }
+                _ => (),
             },
-            Some("D") => {
-                print!("\nDEATH - - - ");
-                match self.game.spell_deathspell() {
-                    Ok(CombatEvent::Hit(hr)) => {
-                        println!("HIS\n");
-                        self.monster_defeated_message(hr, m_art, m_name);
-                    }
-                    Ok(CombatEvent::Died) => {
-                        println!("YOURS\n");
-                    }
-                    Ok(any) => panic!("Unexpected: {:#?}", any),
-                    Err(err) => panic!("{:#?}", err),
+            Ok(CombatEvent::Healed(st)) => {
+                println!("\nYOU FEEL {} POINTS STRONGER", st);
+            }
+            Ok(CombatEvent::Died) => {
+                if spell == SpellType::Deathspell {
+                    println!("YOURS\n");
+                }
+            }
+            Ok(any) => panic!("Unexpected: {:#?}", any),
+            Err(err) => panic!("{:#?}", err),
+        }
+    }
+
+    /// List spell names for a prompt, e.g. "WEB, FIREBALL, OR DEATHSPELL"
+    fn spell_list(spells: &[SpellType]) -> String {
+        let names: Vec<String> = spells.iter().map(|&s| UI::spell_name(s)).collect();
+
+        match names.len() {
+            0 => String::new(),
+            1 => names[0].clone(),
+            2 => format!("{} OR {}", names[0], names[1]),
+            n => format!("{}, OR {}", names[..n - 1].join(", "), names[n - 1]),
+        }
+    }
+
+    /// Pick the spell the player typed from a list
+    fn choose_spell(spells: &[SpellType], input: &str) -> Option<SpellType> {
+        let input = input.trim();
+
+        if input.is_empty() {
+            return None;
+        }
+
+        spells
+            .iter()
+            .copied()
+            .find(|&s| UI::spell_name(s).starts_with(input))
+    }
+
+    /// Cast a spell outside of combat
+    ///
+    /// Returns true if the player moved
+    fn cast(&mut self) -> bool {
+        let spells = self.game.available_spells(false);
+
+        if spells.is_empty() {
+            println!("** YOU DON'T KNOW ANY SPELLS, {}", self.race_str());
+            return false;
+        }
+
+        let prompt = format!("WHICH SPELL ({})? ", UI::spell_list(&spells));
+
+        let spell = match UI::choose_spell(&spells, &UI::get_input(Some(&prompt))) {
+            Some(s) => s,
+            None => {
+                println!("\n** TRY ONE OF THE OPTIONS GIVEN");
+                return false;
+            }
+        };
+
+        match self.game.cast(spell) {
+            Ok(SpellEvent::Healed(st)) => {
+                println!("\nYOU FEEL {} POINTS STRONGER", st);
+                false
+            }
+            Ok(SpellEvent::Light(rooms)) => {
+                println!("\nA SOFT LIGHT FILLS THE ROOMS AROUND YOU\n");
+
+                for (dir, room_type) in rooms {
+                    println!("{:<6} {}", UI::dir_name(dir), self.room_name(room_type));
                 }
+
+                false
+            }
+            Ok(SpellEvent::DetectMonsters(count)) => {
+                println!("\nYOU SENSE {} MONSTERS ON THIS LEVEL", count);
+                false
             }
-            _ => println!("\n** TRY ONE OF THE OPTIONS GIVEN"),
+            Ok(SpellEvent::Blink) => {
+                println!("\nPOP!");
+                true
+            }
+            Ok(SpellEvent::RemoveCurse(count)) => {
+                if count == 0 {
+                    println!("\nNOTHING HAPPENS");
+                }
+
+                self.report_lifted_curses();
+                false
+            }
+            Ok(SpellEvent::Died) => false,
+            Err(Error::Blind) => {
+                println!("\n** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str());
+                false
+            }
+            Err(err) => panic!("error casting {:#?}", err),
         }
     }
 
     /// Handle combat
     fn combat(&mut self, monster_type: MonsterType) -> bool {
-        let m_name = UI::monster_name(monster_type);
-        let m_art = UI::get_article(&m_name);
+        let m_name = self.monster_name(monster_type);
+        let m_art = self.monster_article(monster_type);
 
         let mut in_combat = true;
         let mut retreated = false;
@@ -898,6 +1503,10 @@ impl UI {
                 GameState::PlayerAttack => {
                     println!("\nYOU'RE FACING {} {}!", m_art, m_name);
 
+                    if let Some(condition) = self.monster_condition() {
+                        println!("\n{}", condition);
+                    }
+
                     print!("\nYOU MAY ATTACK OR RETREAT");
 
                     let can_bribe = self.game.bribe_possible();
@@ -985,6 +1594,10 @@ impl UI {
                     println!("DEXTERITY");
                 }
 
+                if self.game.stash_gp() > 0 || !self.game.stash_treasures().is_empty() {
+                    println!("\nYOUR STASH AT THE ENTRANCE WILL NEVER BE CLAIMED");
+                }
+
                 println!("\nWHEN YOU DIED YOU HAD:\n");
             }
 
@@ -1010,6 +1623,14 @@ impl UI {
                 println!("YOUR MISERABLE LIFE");
             }
 
+            GameState::OutOfTime => {
+                println!("\n{:*^64}\n", "");
+
+                println!("THE CASTLE DOORS SLAM SHUT. YOUR TIME IS UP!\n");
+
+                println!("WHEN TIME RAN OUT YOU HAD:\n");
+            }
+
             GameState::Quit => {
                 println!();
                 println!("A LESS THAN AWE-INSPIRING DEFEAT.\n");
@@ -1021,16 +1642,26 @@ impl UI {
             any => panic!("unexpected game state at end {:#?}", any),
         }
 
-        // List treasures
-        for t in self.game.player_get_treasures() {
+        // List treasures, with any from the stash if the player walked out
+        for t in self.game.final_treasures() {
             println!("{}", UI::treasure_name(t));
         }
 
         // Show weapon
-        println!("{}", UI::weapon_name(self.game.player_weapon_type()));
+        println!("{}", self.player_weapon_name());
 
         // Show armor
-        println!("{}", UI::armor_name(self.game.player_armor_type()));
+        println!("{}", self.player_armor_name());
+
+        // Show shield
+        if self.game.player_shield_type() != ShieldType::None {
+            println!("{}", UI::shield_name(self.game.player_shield_type()));
+        }
+
+        // Show ring
+        if self.game.player_ring_type() != RingType::None {
+            println!("{}", UI::ring_name(self.game.player_ring_type()));
+        }
 
         // Show lamp
         if self.game.player_has_lamp() {
@@ -1041,7 +1672,7 @@ impl UI {
         println!("{} FLARES", self.game.player_flares());
 
         // Show GPs
-        println!("{} GP's", self.game.player_gp());
+        println!("{} GP's", self.game.final_gp());
 
         // Show Runestaff
         if self.game.player_has_runestaff() {
@@ -1050,6 +1681,62 @@ impl UI {
 
         // Show turns
         println!("\nAND IT TOOK YOU {} TURNS!\n", *self.game.turn());
+
+        println!(
+            "YOU PLAYED ON {} DIFFICULTY\n",
+            UI::difficulty_name(self.game.config().difficulty)
+        );
+
+        if let Some(speedrun) = &self.speedrun {
+            for (split, time) in speedrun.splits() {
+                println!("{:<16}{:>10}", UI::split_name(*split), UI::time_str(*time));
+            }
+
+            println!(
+                "{:<16}{:>10}\n",
+                "TOTAL TIME",
+                UI::time_str(speedrun.elapsed())
+            );
+        }
+
+        self.exploration_summary();
+    }
+
+    /// Print how thoroughly the player explored the castle
+    fn exploration_summary(&self) {
+        let stats = self.game.castle_stats();
+
+        let slain = self.game.monsters_slain();
+
+        println!(
+            "YOU EXPLORED {}% OF THE CASTLE AND SLEW {} MONSTER{}",
+            stats.percent_discovered(),
+            slain,
+            if slain == 1 { "" } else { "S" }
+        );
+
+        println!(
+            "YOU FOUND {} OF {} STAIRS AND CLEARED {} ROOMS\n",
+            stats.stairs_found, stats.stairs, stats.cleared
+        );
+
+        println!(
+            "LEFT BEHIND WERE {} MONSTERS, {} VENDORS, {} TREASURES, AND {} ITEMS\n",
+            stats.monsters, stats.vendors, stats.treasures, stats.items
+        );
+
+        for z in 0..self.game.dungeon_zsize() {
+            let level = self.game.level_stats(z);
+
+            println!(
+                "LEVEL {}: {:>3}% EXPLORED, {:>2} ROOMS CLEARED",
+                z + 1,
+                level.percent_discovered(),
+                level.cleared
+            );
+        }
+
+        println!();
     }
 
     /// Ask the user if they want to play again
@@ -1073,28 +1760,35 @@ impl UI {
         }
     }
 
-    /// Sell treasures to a vendor
-    fn vendor_trade_treasures(&mut self) {
+    /// Sell treasures to a vendor, returning false if the vendor walked away
+    fn vendor_trade_treasures(&mut self) -> bool {
         let treasures = self.game.player_get_treasures();
 
         if treasures.is_empty() {
-            return;
+            return true;
         }
 
         println!();
 
-        let price_hash = match self.game.vendor_treasure_offer() {
-            Ok(hash) => hash,
+        match self.game.vendor_treasure_offer() {
+            Ok(_) => (),
+            Err(Error::Cursed) => {
+                println!("THE CURSE OF GREED WON'T LET YOU PART WITH YOUR TREASURES");
+                return true;
+            }
             Err(err) => panic!("{:?}", err),
-        };
+        }
+
+        println!("(ANSWER YES OR NO, OR NAME YOUR OWN PRICE)\n");
 
         for t in treasures {
-            let price = match price_hash.get(&t) {
-                Some(p) => p,
-                None => {
-                    panic!("price missing from hash");
-                }
-            };
+            // Earlier sales may have emptied the vendor's purse
+            let mut price = self.game.vendor_treasure_price(t);
+
+            if price == 0 {
+                println!("I CAN'T AFFORD {} NOW\n", UI::treasure_name(t));
+                continue;
+            }
 
             loop {
                 let yn = UI::get_input(Some(&format!(
@@ -1103,10 +1797,42 @@ impl UI {
                     price
                 )));
 
+                if let Ok(ask) = yn.parse::<u32>() {
+                    match self.game.vendor_treasure_counter(t, ask) {
+                        Ok(HaggleEvent::Accepted(gp)) => {
+                            println!("\nDONE! {} GP's IT IS\n", gp);
+                            break;
+                        }
+                        Ok(HaggleEvent::Countered(p)) => {
+                            println!("\nI COULD GO AS HIGH AS {} GP's\n", p);
+                            price = p;
+                        }
+                        Ok(HaggleEvent::Refused) => {
+                            println!("\nTHAT'S MY FINAL OFFER\n");
+                        }
+                        Ok(HaggleEvent::WalkedAway) => {
+                            println!("\nTHE VENDOR HAS HAD ENOUGH OF YOUR HAGGLING AND WALKS AWAY");
+                            return false;
+                        }
+                        Err(Error::VendorOutOfGold) | Err(Error::VendorNoTreasure) => {
+                            println!("\n** I CAN'T PAY FOR THAT ANY MORE\n");
+                            break;
+                        }
+                        Err(Error::BadAmount) => {
+                            println!("\n** IF YOU WANT TO GIVE IT AWAY, SAY YES\n");
+                        }
+                        Err(err) => panic!("vendor treasure counter: {:#?}", err),
+                    }
+                    continue;
+                }
+
                 match yn.get(..1) {
                     Some("Y") => {
                         match self.game.vendor_treasure_accept(t) {
                             Ok(_) => (),
+                            Err(Error::VendorOutOfGold) | Err(Error::VendorNoTreasure) => {
+                                println!("\n** I CAN'T PAY FOR THAT ANY MORE\n")
+                            }
                             Err(err) => panic!("vendor treasure accept: {:#?}", err),
                         }
                         break;
@@ -1115,41 +1841,47 @@ impl UI {
                         break;
                     }
                     _ => {
-                        println!("\n** ANSWER YES OR NO");
+                        println!("\n** ANSWER YES OR NO, OR NAME YOUR PRICE");
                     }
                 }
             }
         }
+
+        true
     }
 
     /// Trade armor
     fn vendor_trade_armor(&mut self) {
-        let leather_cost = Armor::cost(ArmorType::Leather, true);
-
-        if self.game.player_gp() < leather_cost {
+        let in_stock: Vec<ArmorType> = [ArmorType::Leather, ArmorType::Chainmail, ArmorType::Plate]
+            .into_iter()
+            .filter(|a| self.game.vendor_has(Ware::Armor(*a)))
+            .collect();
+
+        let affordable: Vec<ArmorType> = in_stock
+            .iter()
+            .copied()
+            .filter(|a| self.game.vendor_can_afford_armor_type(*a))
+            .collect();
+
+        if affordable.is_empty() {
             return;
         }
 
-        let chainmail_cost = Armor::cost(ArmorType::Chainmail, true);
-        let plate_cost = Armor::cost(ArmorType::Plate, true);
-
         println!(
             "\nOK, {}, YOU HAVE {} GOLD PIECES AND {}",
             self.race_str(),
             self.game.player_gp(),
-            UI::armor_name(self.game.player_armor_type())
+            self.player_armor_name()
         );
 
         println!("\nHERE IS A LIST OF ARMOR YOU CAN BUY");
 
-        print!("\nNOTHING<0> LEATHER<{}>", leather_cost);
+        print!("\nNOTHING<0>");
 
-        if self.game.player_gp() >= chainmail_cost {
-            print!(" CHAINMAIL<{}>", chainmail_cost)
-        }
+        for a in affordable {
+            let price = self.game.vendor_price(Ware::Armor(a));
 
-        if self.game.player_gp() >= plate_cost {
-            print!(" PLATE<{}>", plate_cost)
+            print!(" {}<{}>", UI::armor_name(a), price);
         }
 
         println!();
@@ -1157,86 +1889,277 @@ impl UI {
         loop {
             let armor_str = UI::get_input(Some("\nYOUR CHOICE? "));
 
-            match armor_str.get(..1) {
-                Some("P") => match self.game.player_purchase_armor(ArmorType::Plate, true) {
-                    Ok(_) => break,
-                    Err(Error::NotEnoughGP) => println!("\n** YOU CAN'T AFFORD PLATE"),
-                    _ => (),
-                },
-                Some("C") => match self.game.player_purchase_armor(ArmorType::Chainmail, true) {
-                    Ok(_) => break,
-                    Err(Error::NotEnoughGP) => println!("\n** YOU HAVEN'T GOT THAT MUCH CASH"),
-                    _ => (),
-                },
-                Some("L") => {
-                    // If we get to this point we already had enough to buy leather
-                    let _ = self.game.player_purchase_armor(ArmorType::Leather, true);
-                    break;
-                }
+            let armor_type = match armor_str.get(..1) {
+                Some("P") => ArmorType::Plate,
+                Some("C") => ArmorType::Chainmail,
+                Some("L") => ArmorType::Leather,
                 Some("N") => break,
                 _ => {
                     println!("\n** DON'T BE SILLY. CHOOSE A SELECTION");
+                    continue;
                 }
+            };
+
+            if !in_stock.contains(&armor_type) {
+                println!("\n** I'M ALL OUT OF THAT, {}", self.race_str());
+                continue;
+            }
+
+            match self.game.vendor_buy_armor(armor_type) {
+                Ok(_) => break,
+                Err(Error::NotEnoughGP) => println!("\n** YOU HAVEN'T GOT THAT MUCH CASH"),
+                Err(err) => panic!("vendor armor: {:#?}", err),
             }
         }
     }
 
     /// Trade armor
     fn vendor_trade_weapons(&mut self) {
-        let dagger_cost = Weapon::cost(WeaponType::Dagger, true);
-
-        if self.game.player_gp() < dagger_cost {
+        let in_stock: Vec<WeaponType> = [WeaponType::Dagger, WeaponType::Mace, WeaponType::Sword]
+            .into_iter()
+            .filter(|w| self.game.vendor_has(Ware::Weapon(*w)))
+            .collect();
+
+        let affordable: Vec<WeaponType> = in_stock
+            .iter()
+            .copied()
+            .filter(|w| self.game.vendor_can_afford_weapon_type(*w))
+            .collect();
+
+        if affordable.is_empty() {
             return;
         }
 
-        let mace_cost = Weapon::cost(WeaponType::Mace, true);
-        let sword_cost = Weapon::cost(WeaponType::Sword, true);
-
         println!(
             "\nYOU HAVE {} GP's LEFT WITH {} IN HAND",
             self.game.player_gp(),
-            UI::weapon_name(self.game.player_weapon_type())
+            self.player_weapon_name()
         );
 
         println!("\nHERE IS A LIST OF ARMOR YOU CAN BUY");
 
-        print!("\nNOTHING<0> DAGGER<{}>", dagger_cost);
+        print!("\nNOTHING<0>");
 
-        if self.game.player_gp() >= mace_cost {
-            print!(" MACE<{}>", mace_cost)
-        }
+        for w in affordable {
+            let price = self.game.vendor_price(Ware::Weapon(w));
 
-        if self.game.player_gp() >= sword_cost {
-            print!(" SWORD<{}>", sword_cost)
+            print!(" {}<{}>", UI::weapon_name(w), price);
         }
 
         println!();
 
         loop {
-            let armor_str = UI::get_input(Some("\nYOUR CHOICE? "));
+            let weapon_str = UI::get_input(Some("\nYOUR CHOICE? "));
 
-            match armor_str.get(..1) {
-                Some("S") => match self.game.player_purchase_weapon(WeaponType::Sword, true) {
-                    Ok(_) => break,
-                    Err(Error::NotEnoughGP) => {
-                        println!("\n** DUNGEON EXPRESS CARD - YOU LEFT HOME WITHOUT IT!")
-                    }
-                    _ => (),
-                },
-                Some("M") => match self.game.player_purchase_weapon(WeaponType::Mace, true) {
-                    Ok(_) => break,
+            let weapon_type = match weapon_str.get(..1) {
+                Some("S") => WeaponType::Sword,
+                Some("M") => WeaponType::Mace,
+                Some("D") => WeaponType::Dagger,
+                Some("N") => break,
+                _ => {
+                    println!("\n** TRY CHOOSING A SELECTION");
+                    continue;
+                }
+            };
+
+            if !in_stock.contains(&weapon_type) {
+                println!("\n** I'M ALL OUT OF THAT, {}", self.race_str());
+                continue;
+            }
+
+            match self.game.vendor_buy_weapon(weapon_type) {
+                Ok(_) => break,
+                Err(Error::NotEnoughGP) => {
+                    println!("\n** DUNGEON EXPRESS CARD - YOU LEFT HOME WITHOUT IT!")
+                }
+                Err(err) => panic!("vendor weapon: {:#?}", err),
+            }
+        }
+    }
+
+    /// Buy shields, rings, and enchanted gear from a vendor
+    fn vendor_trade_equipment(&mut self) {
+        loop {
+            let goods: Vec<Equipment> = match self.game.vendor_stock() {
+                Some(stock) => stock
+                    .wares()
+                    .iter()
+                    .filter(|(w, _)| self.game.vendor_price(*w) <= self.game.player_gp())
+                    .filter_map(|(w, _)| match w {
+                        Ware::Equipment(e) => Some(*e),
+                        _ => None,
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            if goods.is_empty() {
+                return;
+            }
+
+            println!(
+                "\nYOU HAVE {} GP's. I ALSO HAVE SOME RARE GOODS",
+                self.game.player_gp()
+            );
+
+            println!("\n  0. NOTHING<0>");
+
+            for (i, e) in goods.iter().enumerate() {
+                let price = self.game.vendor_price(Ware::Equipment(*e));
+
+                println!("  {}. {}<{}>", i + 1, UI::equipment_name(*e), price);
+            }
+
+            let choice = UI::get_input(Some("\nYOUR CHOICE? "));
+
+            match choice.parse::<usize>() {
+                Ok(0) => return,
+                Ok(n) if n <= goods.len() => match self.game.vendor_buy_equipment(goods[n - 1]) {
+                    Ok(_) => println!("\nA FINE CHOICE, {}", self.race_str()),
                     Err(Error::NotEnoughGP) => println!("\n** SORRY SIR, I DON'T GIVE CREDIT"),
-                    _ => (),
+                    Err(err) => panic!("vendor equipment: {:#?}", err),
                 },
-                Some("D") => {
-                    // If we get to this &point we already had enough to buy a dagger
-                    let _ = self.game.player_purchase_weapon(WeaponType::Dagger, true);
+                _ => println!("\n** TRY CHOOSING A SELECTION"),
+            }
+        }
+    }
+
+    /// Buy flares, potions, and other supplies from a vendor
+    fn vendor_trade_supplies(&mut self) {
+        loop {
+            let supplies: Vec<(Item, u32)> = match self.game.vendor_stock() {
+                Some(stock) => stock
+                    .wares()
+                    .iter()
+                    .filter(|(w, _)| self.game.vendor_price(*w) <= self.game.player_gp())
+                    .filter_map(|(w, n)| match w {
+                        Ware::Supply(i) => Some((*i, *n)),
+                        _ => None,
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+
+            if supplies.is_empty() {
+                return;
+            }
+
+            println!(
+                "\nYOU HAVE {} GP's. I CAN SELL YOU SUPPLIES",
+                self.game.player_gp()
+            );
+
+            println!("\n  0. NOTHING<0>");
+
+            for (i, (item, count)) in supplies.iter().enumerate() {
+                let price = self.game.vendor_price(Ware::Supply(*item));
+                let stack = Stack {
+                    item: *item,
+                    count: *count,
+                };
+
+                println!("  {}. {}<{} EACH>", i + 1, UI::stack_name(stack), price);
+            }
+
+            let choice = UI::get_input(Some("\nYOUR CHOICE? "));
+
+            let (item, in_stock) = match choice.parse::<usize>() {
+                Ok(0) => return,
+                Ok(n) if n <= supplies.len() => supplies[n - 1],
+                _ => {
+                    println!("\n** TRY CHOOSING A SELECTION");
+                    continue;
+                }
+            };
+
+            let price = self.game.vendor_price(Ware::Supply(item));
+            let most = std::cmp::min(in_stock, self.game.player_gp() / price.max(1));
+
+            let count = loop {
+                let count_str = UI::get_input(Some(&format!("\nHOW MANY (UP TO {})? ", most)));
+
+                match count_str.parse::<u32>() {
+                    Ok(n) if n <= most => break n,
+                    _ => println!("\n** I CAN'T SELL YOU THAT MANY"),
+                }
+            };
+
+            if count == 0 {
+                continue;
+            }
+
+            match self.game.vendor_buy_supply(item, count) {
+                Ok(()) => println!("\nA FINE CHOICE, {}", self.race_str()),
+                Err(Error::InventoryFull) => {
+                    println!("\n** YOU'VE NO ROOM TO CARRY IT, {}", self.race_str())
+                }
+                Err(err) => panic!("vendor supplies: {:#?}", err),
+            }
+        }
+    }
+
+    /// Have a vendor lift curses
+    fn vendor_cure(&mut self) {
+        let cost = self.game.vendor_cure_cost();
+
+        if cost == 0 {
+            println!("\n** I CAN'T HELP YOU WITH THAT, {}", self.race_str());
+            return;
+        }
+
+        loop {
+            let yn = UI::get_input(Some(&format!(
+                "\nI'LL LIFT YOUR CURSES FOR {} GP's. AGREED? ",
+                cost
+            )));
+
+            match yn.get(..1) {
+                Some("Y") => {
+                    match self.game.vendor_cure() {
+                        Ok(_) => {
+                            self.report_lifted_curses();
+                        }
+                        Err(Error::NotEnoughGP) => {
+                            println!("\n** COME BACK WHEN YOU CAN PAY, {}", self.race_str())
+                        }
+                        Err(err) => panic!("vendor cure: {:#?}", err),
+                    }
                     break;
                 }
                 Some("N") => break,
-                _ => {
-                    println!("\n** TRY CHOOSING A SELECTION");
+                _ => println!("\n** ANSWER YES OR NO"),
+            }
+        }
+    }
+
+    /// Have a vendor repair weapon and armor
+    fn vendor_repair(&mut self) {
+        let cost = self.game.vendor_repair_cost();
+
+        if cost == 0 {
+            println!("\n** YOUR GEAR IS IN FINE SHAPE, {}", self.race_str());
+            return;
+        }
+
+        loop {
+            let yn = UI::get_input(Some(&format!(
+                "\nI'LL MEND YOUR GEAR FOR {} GP's. AGREED? ",
+                cost
+            )));
+
+            match yn.get(..1) {
+                Some("Y") => {
+                    match self.game.vendor_repair() {
+                        Ok(_) => println!("\nGOOD AS NEW!"),
+                        Err(Error::NotEnoughGP) => {
+                            println!("\n** COME BACK WHEN YOU CAN PAY, {}", self.race_str())
+                        }
+                        Err(err) => panic!("vendor repair: {:#?}", err),
+                    }
+                    break;
                 }
+                Some("N") => break,
+                _ => println!("\n** ANSWER YES OR NO"),
             }
         }
     }
@@ -1254,12 +2177,18 @@ impl UI {
                 break;
             }
 
+            if !self.game.vendor_has(Ware::Potion(*s)) {
+                i += 1;
+                continue;
+            }
+
             let stat_name = UI::stat_name(*s);
 
             loop {
                 let play_again = UI::get_input(Some(&format!(
-                    "\nWANT TO BUY A POTION OF {} FOR 1000 GP's? ",
-                    stat_name
+                    "\nWANT TO BUY A POTION OF {} FOR {} GP's? ",
+                    stat_name,
+                    self.game.vendor_price(Ware::Potion(*s))
                 )));
 
                 match play_again.get(..1) {
@@ -1286,17 +2215,26 @@ impl UI {
 
     /// Buy a lamp from the vendor
     fn vendor_buy_lamp(&mut self) {
-        if self.game.player_has_lamp() || !self.game.vendor_can_afford_lamp() {
+        if self.game.player_has_lamp()
+            || !self.game.vendor_can_afford_lamp()
+            || !self.game.vendor_has(Ware::Lamp)
+        {
             return;
         }
 
         loop {
-            let lamp = UI::get_input(Some("\nWANT A LAMP FOR OR 1000 GP's? "));
+            let lamp = UI::get_input(Some(&format!(
+                "\nWANT A LAMP FOR OR {} GP's? ",
+                self.game.vendor_price(Ware::Lamp)
+            )));
 
             match lamp.get(..1) {
                 Some("Y") => {
                     match self.game.vendor_buy_lamp() {
                         Ok(()) => println!("\nIT'S GUARANTEED TO OUTLIVE YOU!"),
+                        Err(Error::InventoryFull) => {
+                            println!("\n** YOU'VE NO ROOM TO CARRY IT, {}", self.race_str())
+                        }
                         Err(err) => panic!("{:#?}", err),
                     }
                     break;
@@ -1313,7 +2251,12 @@ impl UI {
 
     /// Trade with a Vendor
     fn vendor_trade(&mut self) {
-        self.vendor_trade_treasures();
+        if !self.vendor_trade_treasures() {
+            return;
+        }
+
+        // Supplies come cheap, so even the poor can buy them
+        self.vendor_trade_supplies();
 
         if self.game.player_gp() < 1000 {
             println!("\n** YOU'RE TOO POOR TO TRADE");
@@ -1322,14 +2265,29 @@ impl UI {
 
         self.vendor_trade_armor();
         self.vendor_trade_weapons();
+        self.vendor_trade_equipment();
         self.vendor_buy_stats();
         self.vendor_buy_lamp();
     }
 
     /// Interact with a Vendor
     pub fn vendor(&mut self) -> bool {
+        match self.game.vendor_reputation() {
+            Some(r) if r >= 25 => println!("THE VENDOR GREETS YOU LIKE AN OLD FRIEND"),
+            Some(r) if r <= -25 => println!("THE VENDOR EYES YOU WARILY"),
+            _ => (),
+        }
+
         println!("YOU MAY TRADE WITH, ATTACK, OR IGNORE THE VENDOR");
 
+        if self.game.vendor_repair_cost() > 0 {
+            println!("THE VENDOR ALSO OFFERS TO (R)EPAIR YOUR GEAR");
+        }
+
+        if self.game.vendor_cure_cost() > 0 {
+            println!("THE VENDOR CAN (C)URE YOUR CURSES");
+        }
+
         let mut fighting_vendor = false;
 
         loop {
@@ -1347,6 +2305,8 @@ impl UI {
                     fighting_vendor = true;
                     break;
                 }
+                Some("R") => self.vendor_repair(),
+                Some("C") => self.vendor_cure(),
                 Some("I") => {
                     self.game.vendor_complete();
                     break;
@@ -1365,149 +2325,480 @@ impl UI {
             return false;
         }
 
-        if !self.game.player_has_lamp() {
+        if !self.game.player_has_light() {
             println!("** YOU DON'T HAVE A LAMP");
             return false;
         }
 
-        let dir_str = UI::get_input(Some("WHERE DO YOU WANT TO SHINE THE LAMP (N,S,E, OR W)? "));
-
-        let dir = match dir_str.get(..1) {
-            Some("N") => Direction::North,
-            Some("S") => Direction::South,
-            Some("W") => Direction::West,
-            Some("E") => Direction::East,
-            _ => {
-                println!("\n** TURKEY! THAT'S NOT A DIRECTION");
-                return false;
-            }
+        let dir_str = UI::get_input(Some("WHERE DO YOU WANT TO SHINE THE LAMP (N,S,E, OR W)? "));
+
+        let dir = match dir_str.get(..1) {
+            Some("N") => Direction::North,
+            Some("S") => Direction::South,
+            Some("W") => Direction::West,
+            Some("E") => Direction::East,
+            _ => {
+                println!("\n** TURKEY! THAT'S NOT A DIRECTION");
+                return false;
+            }
+        };
+
+        let (x, y, z, room_type);
+
+        match self.game.shine_lamp(dir) {
+            Ok((tx, ty, tz, troom_type)) => {
+                x = tx;
+                y = ty;
+                z = tz;
+                room_type = troom_type;
+            }
+            Err(Error::Cursed) => {
+                println!("** YOUR LAMP GIVES NO LIGHT IN THIS CURSED DARKNESS");
+                return false;
+            }
+            Err(err) => panic!("{:?}", err),
+        }
+
+        let (a, b) = self.order_coords(x, y);
+
+        println!(
+            "\nTHE LAMP SHINES INTO ({},{}) LEVEL {}\n",
+            a + 1,
+            b + 1,
+            z + 1
+        );
+
+        let room_str = self.room_name(room_type);
+
+        println!("THERE YOU'LL FIND {}", room_str);
+
+        true
+    }
+
+    /// Set off a flare
+    pub fn flare(&mut self) -> bool {
+        if self.game.player_is_blind() {
+            println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str());
+            return false;
+        }
+
+        if self.game.player_flares() == 0 {
+            println!("** HEY BRIGHT ONE, YOU'RE OUT OF FLARES");
+            return false;
+        }
+
+        if let Err(err) = self.game.flare() {
+            panic!("{:?}", err);
+        }
+
+        let xm1 = self.game.player_x() as i32 - 1;
+        let ym1 = self.game.player_y() as i32 - 1;
+
+        let z = self.game.player_z();
+
+        let view = self.game.player_view();
+
+        for y in ym1..(ym1 + 3) {
+            let yw = self.game.wrap_y(y);
+
+            for x in xm1..(xm1 + 3) {
+                let xw = self.game.wrap_x(x);
+
+                if x == xm1 {
+                    print!(" ");
+                }
+
+                match view.room_at(xw, yw, z) {
+                    Some(room_type) => print!("{}", UI::room_char(room_type)),
+                    None => print!("?"),
+                }
+
+                if x == xm1 || x == xm1 + 1 {
+                    print!("     ");
+                }
+            }
+
+            println!("\n");
+        }
+
+        true
+    }
+
+    /// Gaze into an Orb
+    pub fn gaze(&mut self) -> bool {
+        let mut success = false;
+
+        match self.game.gaze() {
+            Ok(event) => {
+                print!("YOU SEE ");
+
+                match event {
+                    OrbEvent::BloodyHeap => println!("YOURSELF IN A BLOODY HEAP"),
+                    OrbEvent::Polymorph(m) => {
+                        println!(
+                            "YOURSELF DRINKING FROM A POOL AND BECOMING {} {}",
+                            self.monster_article(m),
+                            self.monster_name(m)
+                        );
+                    }
+                    OrbEvent::GazeBack(m) => {
+                        println!(
+                            "{} {} GAZING BACK AT YOU",
+                            self.monster_article(m),
+                            self.monster_name(m)
+                        );
+                    }
+                    OrbEvent::Item(room_type, x, y, z) => {
+                        let (a, b) = self.order_coords(x, y);
+
+                        println!(
+                            "{} AT ({},{}) LEVEL {}",
+                            self.room_name(room_type),
+                            a + 1,
+                            b + 1,
+                            z + 1
+                        );
+                    }
+                    OrbEvent::OrbOfZot(x, y, z) => {
+                        let (a, b) = self.order_coords(x, y);
+
+                        println!("THE ORB OF ZOT AT ({},{}) LEVEL {}", a + 1, b + 1, z + 1);
+                    }
+                    OrbEvent::SoapOpera => {
+                        println!("A SOAP OPERA RERUN");
+                    }
+                }
+
+                println!();
+
+                success = true;
+            }
+            Err(Error::Blind) => println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str()),
+            Err(Error::CantGo) => println!("** NO ORB - NO GAZE"),
+            Err(Error::TooSoon) => println!("** THE PALANTIR IS STILL CLOUDED"),
+            _ => panic!("SNH"),
+        }
+
+        success
+    }
+
+    /// Offer to use equipment found in a chest
+    fn chest_equipment(&mut self, equipment: Equipment) {
+        println!("YOU FIND {}", UI::equipment_name(equipment));
+
+        loop {
+            let yn = UI::get_input(Some("\nDO YOU WANT TO USE IT? "));
+
+            match yn.get(..1) {
+                Some("Y") => {
+                    match self.game.take_found_equipment() {
+                        Ok(_) => println!("\nIT SUITS YOU, {}", self.race_str()),
+                        Err(err) => panic!("taking equipment: {:#?}", err),
+                    }
+                    break;
+                }
+                Some("N") => {
+                    self.game.decline_found_equipment();
+                    break;
+                }
+                _ => println!("\n** ANSWER YES OR NO"),
+            }
+        }
+    }
+
+    /// True if an item the player just found had to be left in the room
+    fn left_on_floor(&self, item: Item) -> bool {
+        self.game.room_items().iter().any(|s| s.item == item)
+    }
+
+    /// Show what the player is carrying
+    fn inventory(&self) {
+        let stacks = self.game.player_inventory();
+
+        let kinds = stacks.iter().filter(|s| s.item.takes_slot()).count();
+
+        match self.game.player_inventory_capacity() {
+            Some(capacity) => println!(
+                "YOU ARE CARRYING {} OF {} KINDS OF THINGS YOU CAN HOLD\n",
+                kinds, capacity
+            ),
+            None => println!("YOU ARE CARRYING {} KINDS OF THINGS\n", kinds),
+        }
+
+        for s in stacks {
+            println!("  {}", UI::stack_name(s));
+        }
+
+        let curses = self.game.player_curses();
+
+        if !curses.is_empty() {
+            println!("\nYOU ARE CURSED WITH\n");
+        }
+
+        for c in curses {
+            let mut line = UI::curse_name(c.curse_type);
+
+            if c.stacks > 1 {
+                line += &format!(" X{}", c.stacks);
+            }
+
+            if let Some(turns) = c.turns_left {
+                line += &format!(" FOR {} MORE TURNS", turns);
+            }
+
+            println!("  {}", line);
+        }
+    }
+
+    /// Pick one of a list of stacks
+    fn choose_stack(stacks: &[Stack], prompt: &str) -> Option<Stack> {
+        if stacks.len() == 1 {
+            return Some(stacks[0]);
+        }
+
+        println!("  0. NOTHING");
+
+        for (i, s) in stacks.iter().enumerate() {
+            println!("  {}. {}", i + 1, UI::stack_name(*s));
+        }
+
+        loop {
+            let choice = UI::get_input(Some(prompt));
+
+            match choice.parse::<usize>() {
+                Ok(0) => return None,
+                Ok(n) if n <= stacks.len() => return Some(stacks[n - 1]),
+                _ => println!("\n** TRY CHOOSING A SELECTION"),
+            }
+        }
+    }
+
+    /// Ask how many of a stack to move, all of it by default
+    fn choose_count(stack: Stack) -> u32 {
+        if stack.count == 1 {
+            return 1;
+        }
+
+        loop {
+            let count_str = UI::get_input(Some(&format!("\nHOW MANY (UP TO {})? ", stack.count)));
+
+            if count_str.is_empty() {
+                return stack.count;
+            }
+
+            match count_str.parse::<u32>() {
+                Ok(n) if n <= stack.count => return n,
+                _ => println!("\n** YOU DON'T HAVE THAT MANY"),
+            }
+        }
+    }
+
+    /// Drop something in the current room
+    fn drop_item(&mut self) -> bool {
+        let stacks = self.game.player_inventory();
+
+        if stacks.is_empty() {
+            println!("** YOU AREN'T CARRYING ANYTHING, {}", self.race_str());
+            return false;
+        }
+
+        let stack = match UI::choose_stack(&stacks, "\nWHAT DO YOU WANT TO DROP? ") {
+            Some(s) => s,
+            None => return false,
         };
 
-        let (x, y, z, room_type);
+        let count = UI::choose_count(stack);
 
-        match self.game.shine_lamp(dir) {
-            Ok((tx, ty, tz, troom_type)) => {
-                x = tx;
-                y = ty;
-                z = tz;
-                room_type = troom_type;
+        match self.game.drop_item(stack.item, count) {
+            Ok(0) => false,
+            Ok(n) => {
+                let moved = Stack { count: n, ..stack };
+                println!("\nYOU DROP {}", UI::stack_name(moved));
+                true
             }
-            Err(err) => panic!("{:?}", err),
+            Err(Error::NotCarrying) => false,
+            Err(err) => panic!("dropping item: {:#?}", err),
         }
+    }
 
-        println!(
-            "\nTHE LAMP SHINES INTO ({},{}) LEVEL {}\n",
-            x + 1,
-            y + 1,
-            z + 1
-        );
-
-        let room_str = UI::room_name(&room_type);
+    /// Ask how much gold to move, all of it by default
+    fn choose_gp(most: u32) -> u32 {
+        loop {
+            let amount = UI::get_input(Some(&format!("\nHOW MUCH GOLD (UP TO {})? ", most)));
 
-        println!("THERE YOU'LL FIND {}", room_str);
+            if amount.is_empty() {
+                return most;
+            }
 
-        true
+            match amount.parse::<u32>() {
+                Ok(n) if n <= most => return n,
+                _ => println!("\n** YOU DON'T HAVE THAT MUCH"),
+            }
+        }
     }
 
-    /// Set off a flare
-    pub fn flare(&mut self) -> bool {
-        if self.game.player_is_blind() {
-            println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str());
-            return false;
+    /// Pick gold or one of a list of treasures
+    ///
+    /// Returns None for gold
+    fn choose_gp_or_treasure(
+        gp: u32,
+        treasures: &[TreasureType],
+        prompt: &str,
+    ) -> Option<Option<TreasureType>> {
+        println!("\n  0. NOTHING");
+        println!("  1. {} GP's", gp);
+
+        for (i, t) in treasures.iter().enumerate() {
+            println!("  {}. {}", i + 2, UI::treasure_name(*t));
         }
 
-        if self.game.player_flares() == 0 {
-            println!("** HEY BRIGHT ONE, YOU'RE OUT OF FLARES");
-            return false;
+        loop {
+            let choice = UI::get_input(Some(prompt));
+
+            match choice.parse::<usize>() {
+                Ok(0) => return None,
+                Ok(1) => return Some(None),
+                Ok(n) if n <= treasures.len() + 1 => return Some(Some(treasures[n - 2])),
+                _ => println!("\n** TRY CHOOSING A SELECTION"),
+            }
         }
+    }
 
-        if let Err(err) = self.game.flare() {
-            panic!("{:?}", err);
+    /// Bank gold and treasures at the entrance, or take them back out
+    ///
+    /// Returns true if anything changed hands
+    fn bank(&mut self) -> bool {
+        if self.game.player_view().here() != RememberedRoom::Entrance {
+            println!("** YOUR STASH IS BACK AT THE ENTRANCE, {}", self.race_str());
+            return false;
         }
 
-        let xm1 = self.game.player_x() as i32 - 1;
-        let ym1 = self.game.player_y() as i32 - 1;
+        let mut banked = false;
 
-        let z = self.game.player_z();
+        loop {
+            println!("\nYOUR STASH HOLDS {} GP's", self.game.stash_gp());
 
-        for y in ym1..(ym1 + 3) {
-            let yw = self.game.wrap_y(y);
+            for t in self.game.stash_treasures() {
+                println!("{}", UI::treasure_name(t));
+            }
 
-            for x in xm1..(xm1 + 3) {
-                let xw = self.game.wrap_x(x);
+            let choice = UI::get_input(Some("\n(D)EPOSIT, (W)ITHDRAW, OR (L)EAVE? "));
 
-                let room_type = self.game.dungeon_room_at(xw, yw, z).room_type();
+            let result = match choice.get(..1) {
+                Some("D") => {
+                    let gp = self.game.player_gp();
+                    let treasures = self.game.player_get_treasures();
 
-                if x == xm1 {
-                    print!(" ");
+                    match UI::choose_gp_or_treasure(gp, &treasures, "\nDEPOSIT WHAT? ") {
+                        Some(None) => self.game.stash_deposit_gp(UI::choose_gp(gp)),
+                        Some(Some(t)) => self.game.stash_deposit_treasure(t),
+                        None => continue,
+                    }
                 }
+                Some("W") => {
+                    let gp = self.game.stash_gp();
+                    let treasures = self.game.stash_treasures();
 
-                print!("{}", UI::room_char(room_type));
+                    match UI::choose_gp_or_treasure(gp, &treasures, "\nWITHDRAW WHAT? ") {
+                        Some(None) => self.game.stash_withdraw_gp(UI::choose_gp(gp)),
+                        Some(Some(t)) => self.game.stash_withdraw_treasure(t),
+                        None => continue,
+                    }
+                }
+                Some("L") => return banked,
+                _ => {
+                    println!("\n** ANSWER D, W, OR L");
+                    continue;
+                }
+            };
 
-                if x == xm1 || x == xm1 + 1 {
-                    print!("     ");
+            match result {
+                Ok(()) => banked = true,
+                Err(Error::InventoryFull) => {
+                    println!("\n** YOU'VE NO ROOM TO CARRY IT, {}", self.race_str())
                 }
+                Err(err) => panic!("stash: {:#?}", err),
             }
+        }
+    }
 
-            println!("\n");
+    /// Use up a potion, scroll, or other supply
+    fn use_item(&mut self) -> bool {
+        let stacks: Vec<Stack> = self
+            .game
+            .player_inventory()
+            .into_iter()
+            .filter(|s| s.item.is_usable())
+            .collect();
+
+        if stacks.is_empty() {
+            println!("** YOU HAVE NOTHING TO USE, {}", self.race_str());
+            return false;
         }
 
-        true
+        let stack = match UI::choose_stack(&stacks, "\nWHAT DO YOU WANT TO USE? ") {
+            Some(s) => s,
+            None => return false,
+        };
+
+        match self.game.use_item(stack.item) {
+            Ok(UseEvent::Healed(st)) => {
+                println!("\nYOU FEEL STRONGER (+{} ST)", st);
+                true
+            }
+            Ok(UseEvent::Sight) => {
+                println!("\nTHE FOG LIFTS - YOU CAN SEE AGAIN!");
+                true
+            }
+            Ok(UseEvent::Mapped(z)) => {
+                println!("\nTHE SCROLL SHOWS YOU ALL OF LEVEL {}", z + 1);
+                true
+            }
+            Ok(UseEvent::Unstuck) => {
+                println!("\nTHE BOOK SLIDES FROM YOUR HANDS");
+                true
+            }
+            Err(Error::NotNeeded) => {
+                println!("\n** IT WOULDN'T DO YOU ANY GOOD NOW");
+                false
+            }
+            Err(Error::Blind) => {
+                println!("\n** YOU CAN'T READ IT, BLIND {}", self.race_str());
+                false
+            }
+            Err(err) => panic!("using item: {:#?}", err),
+        }
     }
 
-    /// Gaze into an Orb
-    pub fn gaze(&mut self) -> bool {
-        let mut success = false;
+    /// Pick up something from the current room
+    fn take_item(&mut self) -> bool {
+        let stacks = self.game.room_items();
 
-        match self.game.gaze() {
-            Ok(event) => {
-                print!("YOU SEE ");
+        if stacks.is_empty() {
+            println!("** THERE'S NOTHING HERE TO TAKE, {}", self.race_str());
+            return false;
+        }
 
-                match event {
-                    OrbEvent::BloodyHeap => println!("YOURSELF IN A BLOODY HEAP"),
-                    OrbEvent::Polymorph(m) => {
-                        let mon_str = UI::monster_name(m);
-                        println!(
-                            "YOURSELF DRINKING FROM A POOL AND BECOMING {} {}",
-                            UI::get_article(&mon_str),
-                            mon_str
-                        );
-                    }
-                    OrbEvent::GazeBack(m) => {
-                        let mon_str = UI::monster_name(m);
-                        println!(
-                            "{} {} GAZING BACK AT YOU",
-                            UI::get_article(&mon_str),
-                            mon_str
-                        );
-                    }
-                    OrbEvent::Item(room_type, x, y, z) => {
-                        println!(
-                            "{} AT ({},{}) LEVEL {}",
-                            UI::room_name(&room_type),
-                            x + 1,
-                            y + 1,
-                            z + 1
-                        );
-                    }
-                    OrbEvent::OrbOfZot(x, y, z) => {
-                        println!("THE ORB OF ZOT AT ({},{}) LEVEL {}", x + 1, y + 1, z + 1);
-                    }
-                    OrbEvent::SoapOpera => {
-                        println!("A SOAP OPERA RERUN");
-                    }
-                }
+        let stack = match UI::choose_stack(&stacks, "\nWHAT DO YOU WANT TO TAKE? ") {
+            Some(s) => s,
+            None => return false,
+        };
 
-                println!();
+        let count = UI::choose_count(stack);
 
-                success = true;
+        match self.game.take_item(stack.item, count) {
+            Ok(0) => false,
+            Ok(n) => {
+                let moved = Stack { count: n, ..stack };
+                println!("\nYOU TAKE {}", UI::stack_name(moved));
+                true
             }
-            Err(Error::Blind) => println!("** YOU CAN'T SEE ANYTHING, DUMB {}", self.race_str()),
-            Err(Error::CantGo) => println!("** NO ORB - NO GAZE"),
-            _ => panic!("SNH"),
+            Err(Error::InventoryFull) => {
+                println!("\n** YOUR PACK IS FULL. DROP SOMETHING FIRST");
+                false
+            }
+            Err(err) => panic!("taking item: {:#?}", err),
         }
-
-        success
     }
 
     /// Open a chest
@@ -1517,6 +2808,7 @@ impl UI {
                 ChestEvent::Explode => println!("KABOOM! IT EXPLODES"),
                 ChestEvent::Gas => println!("GAS! YOU STAGGER FROM THE ROOM"),
                 ChestEvent::Treasure(amount) => println!("YOU FIND {} GOLD PIECES", amount),
+                ChestEvent::Equipment(equipment) => self.chest_equipment(equipment),
             },
 
             Err(err) => panic!("{:?}", err),
@@ -1534,7 +2826,7 @@ impl UI {
                 }
                 BookEvent::Poetry => println!("IT'S ANOTHER VOLUME OF ZOT'S POETRY! - YEECH!"),
                 BookEvent::PlayMonster(m) => {
-                    println!("IT'S AN OLD COPY OF PLAY{}", UI::monster_name(m))
+                    println!("IT'S AN OLD COPY OF PLAY{}", self.monster_name(m))
                 }
                 BookEvent::Dexterity => println!("IT'S A MANUAL OF DEXTERITY!"),
                 BookEvent::Strength => println!("IT'S A MANUAL OF STRENGTH!"),
@@ -1550,11 +2842,9 @@ impl UI {
 
     /// Open a book or chest
     pub fn open(&mut self) -> bool {
-        let room_type = self.game.room_at_player().room_type().clone();
-
-        match room_type {
-            RoomType::Chest => self.open_chest(),
-            RoomType::Book => self.open_book(),
+        match self.game.player_view().here() {
+            RememberedRoom::Chest => self.open_chest(),
+            RememberedRoom::Book => self.open_book(),
             _ => {
                 println!("** THE ONLY THING YOU OPENED WAS YOUR BIG MOUTH");
                 return false;
@@ -1565,8 +2855,12 @@ impl UI {
     }
 
     /// Display a random message
-    fn rand_message(&mut self) {
-        match self.game.rand_message() {
+    ///
+    /// Returns true if there was a message
+    fn rand_message(&mut self) -> bool {
+        let message = self.game.rand_message();
+
+        match message {
             RandomMessage::SeeBat => println!("\nYOU SEE A BAT FLY BY"),
             RandomMessage::HearSound => {
                 let sounds = ["A SCREAM", "FOOTSTEPS", "A WUMPUS", "THUNDER"];
@@ -1584,6 +2878,8 @@ impl UI {
             RandomMessage::Playing => println!("\nYOU ARE PLAYING WIZARD'S CASTLE"),
             RandomMessage::None => (),
         }
+
+        message != RandomMessage::None
     }
 
     /// Equip player phase
@@ -1597,26 +2893,62 @@ impl UI {
     }
 
     /// Things to do at the start of the turn
-    pub fn at_turn_start(&mut self) {
-        self.game.add_turn(1);
+    ///
+    /// Returns true if something happened that should interrupt exploring
+    pub fn at_turn_start(&mut self) -> bool {
+        self.ambushed = self.game.add_turn(1);
 
         self.game.discover_room_at_player();
 
         self.game.curse_effects();
 
-        self.game.curse_check();
+        let mut noteworthy = self.report_lifted_curses();
+
+        // Classic curses strike without a word, but still cut exploring short
+        if let Some(curse) = self.game.curse_check() {
+            if self.game.config().rules.curse_warning {
+                println!(
+                    "\nYOU FEEL THE CURSE OF {} SETTLE ON YOU",
+                    UI::curse_name(curse)
+                );
+            }
+
+            noteworthy = true;
+        }
+
+        if self.ambushed {
+            println!("\nSOMETHING WANDERS INTO THE ROOM!");
+            noteworthy = true;
+        }
 
-        self.rand_message();
+        noteworthy |= self.rand_message();
 
         // Cure blindness
         if self.game.cure_blindness() {
             println!("\nTHE OPAL EYE CURES YOUR BLINDNESS");
+            noteworthy = true;
         }
 
         // Cure book stuck to hands
         if self.game.cure_book() {
             println!("\nTHE BLUE FLAME DISSOLVES THE BOOK");
+            noteworthy = true;
+        }
+
+        noteworthy
+    }
+
+    /// Tell the player about curses that have lifted
+    ///
+    /// Returns true if any did
+    fn report_lifted_curses(&mut self) -> bool {
+        let lifted = self.game.take_lifted_curses();
+
+        for &curse in &lifted {
+            println!("\nTHE CURSE OF {} LIFTS FROM YOU", UI::curse_name(curse));
         }
+
+        !lifted.is_empty()
     }
 
     /// Quit the game
@@ -1647,27 +2979,216 @@ impl UI {
         println!("(W)EST       (M)AP         (O)PEN");
         println!("(E)AST       (L)AMP        (H)ELP");
         println!("(U)P         (F)LARE       (Q)UIT");
+        println!("(GO) X,Y,Z   (GO) STAIRS   E(X)PLORE [STEPS]");
+        println!("(C)AST A SPELL  (I)NVENTORY  (TA)KE  (DROP)  (US)E  (B)ANK");
+    }
+}
+
+/// Print command line usage
+fn usage() {
+    println!("usage: wizardscastle [options]\n");
+    println!("  --depth-scaled    deeper levels hold tougher monsters");
+    println!("  --monsters FILE   load monster definitions from FILE");
+    println!("  --curses FILE     load curse definitions from FILE");
+    println!("  --economy FILE    load prices from FILE");
+    println!("  --no-abilities    monsters don't use special abilities");
+    println!("  --wandering       monsters move between rooms");
+    println!("  --regeneration    wounded monsters heal over time");
+    println!("  --rules RULES     play by classic or modern rules, or rules in a file");
+    println!("  --difficulty D    easy, normal, hard, or nightmare");
+    println!("  --turn-limit N    escape within N turns");
+    println!("  --weapon-wear N   1 in N melee hits wears your weapon");
+    println!("  --speedrun        time the game, with splits");
+    println!("  --help            show this help");
+}
+
+/// Return the value that goes with an option, or exit if it's missing
+fn option_value(args: &mut impl Iterator<Item = String>, option: &str) -> String {
+    match args.next() {
+        Some(value) => value,
+        None => {
+            eprintln!("{} needs a value\n", option);
+            usage();
+            process::exit(1);
+        }
+    }
+}
+
+/// Build the game options from the command line
+fn parse_args() -> GameConfig {
+    let mut config = GameConfig::default();
+    let mut abilities = true;
+
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--depth-scaled" => config.spawn_mode = SpawnMode::DepthScaled,
+            "--no-abilities" => abilities = false,
+            "--wandering" => config.wandering = true,
+            "--regeneration" => config.regeneration = true,
+            "--speedrun" => config.speedrun = true,
+            "--weapon-wear" => {
+                let hits = option_value(&mut args, &arg);
+
+                config.wear_chance = match hits.parse::<u32>() {
+                    Ok(n) if n > 0 => n,
+                    _ => {
+                        eprintln!("--weapon-wear needs a number of hits\n");
+                        usage();
+                        process::exit(1);
+                    }
+                };
+            }
+            "--turn-limit" => {
+                let turns = option_value(&mut args, &arg);
+
+                config.turn_limit = match turns.parse::<u32>() {
+                    Ok(n) if n > 0 => Some(n),
+                    _ => {
+                        eprintln!("--turn-limit needs a number of turns\n");
+                        usage();
+                        process::exit(1);
+                    }
+                };
+            }
+            "--curses" => {
+                let path = option_value(&mut args, &arg);
+
+                config.curses = match CurseTable::load(&path) {
+                    Ok(table) => table,
+                    Err(Error::BadData(line)) => {
+                        eprintln!("{}: bad curse definition on line {}", path, line);
+                        process::exit(1);
+                    }
+                    Err(_) => {
+                        eprintln!("{}: can't load curse definitions", path);
+                        process::exit(1);
+                    }
+                };
+            }
+            "--difficulty" => {
+                let name = option_value(&mut args, &arg);
+
+                config.difficulty = match Difficulty::from_name(&name) {
+                    Some(difficulty) => difficulty,
+                    None => {
+                        eprintln!("unknown difficulty: {}\n", name);
+                        usage();
+                        process::exit(1);
+                    }
+                };
+            }
+            "--rules" => {
+                let rules = option_value(&mut args, &arg);
+
+                config.rules = match Ruleset::preset(&rules) {
+                    Some(preset) => preset,
+                    None => match Ruleset::load(&rules) {
+                        Ok(loaded) => loaded,
+                        Err(Error::BadData(line)) => {
+                            eprintln!("{}: bad rule on line {}", rules, line);
+                            process::exit(1);
+                        }
+                        Err(_) => {
+                            eprintln!("{}: not a ruleset or a rules file", rules);
+                            process::exit(1);
+                        }
+                    },
+                };
+
+                // A rules file can set prices too
+                if Ruleset::preset(&rules).is_none() {
+                    config.economy = match config.economy.clone().apply_file(&rules) {
+                        Ok(economy) => economy,
+                        Err(Error::BadData(line)) => {
+                            eprintln!("{}: bad economy setting on line {}", rules, line);
+                            process::exit(1);
+                        }
+                        Err(_) => {
+                            eprintln!("{}: can't load economy settings", rules);
+                            process::exit(1);
+                        }
+                    };
+                }
+            }
+            "--economy" => {
+                let path = option_value(&mut args, &arg);
+
+                config.economy = match Economy::load(&path) {
+                    Ok(economy) => economy,
+                    Err(Error::BadData(line)) => {
+                        eprintln!("{}: bad economy setting on line {}", path, line);
+                        process::exit(1);
+                    }
+                    Err(_) => {
+                        eprintln!("{}: can't load economy settings", path);
+                        process::exit(1);
+                    }
+                };
+            }
+            "--monsters" => {
+                let path = option_value(&mut args, &arg);
+
+                config.monsters = match MonsterTable::load(&path) {
+                    Ok(table) => table,
+                    Err(Error::BadData(line)) => {
+                        eprintln!("{}: bad monster definition on line {}", path, line);
+                        process::exit(1);
+                    }
+                    Err(_) => {
+                        eprintln!("{}: can't load monster definitions", path);
+                        process::exit(1);
+                    }
+                };
+            }
+            "--help" | "-h" => {
+                usage();
+                process::exit(0);
+            }
+            _ => {
+                eprintln!("unknown option: {}\n", arg);
+                usage();
+                process::exit(1);
+            }
+        }
     }
+
+    // This holds whichever order it comes in with --rules
+    config.rules.abilities &= abilities;
+
+    config
 }
 
 /// Main
 fn main() {
+    let config = parse_args();
+
     let mut playing = true;
 
-    UI::intro();
+    UI::intro(config.rules.intro);
 
     while playing {
-        let game = Game::new(8, 8, 8);
+        let game = Game::new_with_config(8, 8, 8, config.clone());
 
         let mut ui = UI {
             game,
             rng: rng(),
+            travel: VecDeque::new(),
+            explore_steps: 0,
+            explore_limit: 20,
+            ambushed: false,
+            speedrun: None,
         };
 
         ui.equip();
 
         println!("\n\nOK {}, YOU ENTER THE CASTLE AND BEGIN.", ui.race_str());
 
+        if config.speedrun {
+            ui.speedrun = Some(Speedrun::start());
+        }
+
         let mut alive = true;
         let mut automove = false;
         let mut free_move = false;
@@ -1678,6 +3199,8 @@ fn main() {
         let mut resolve_room_effects = true;
 
         while alive {
+            ui.check_splits();
+
             // See if we were killed by something
             if ui.game.state() == GameState::Dead {
                 alive = false;
@@ -1685,14 +3208,35 @@ fn main() {
             }
 
             if !free_move {
-                ui.at_turn_start();
+                if ui.at_turn_start() {
+                    ui.explore_steps = 0;
+                }
+
+                if ui.game.state() == GameState::OutOfTime {
+                    alive = false;
+                    continue;
+                }
             } else {
                 free_move = false;
             }
 
+            // A wandering monster doesn't wait for the player to move
+            if ui.ambushed {
+                ui.stop_walking();
+                automove = true;
+            }
+
             if automove {
                 println!("\n");
                 automove = false;
+            } else if ui.traveling() {
+                println!("\n");
+                ui.travel_step();
+            } else if ui.exploring() {
+                println!("\n");
+                if !ui.explore_step() {
+                    quiet = true;
+                }
             } else {
                 let mut valid_command = false;
 
@@ -1703,15 +3247,45 @@ fn main() {
 
                     println!();
 
+                    if let Some("US") = command.get(..2) {
+                        if !ui.use_item() {
+                            quiet = true;
+                        }
+                        break;
+                    }
+
+                    if let Some("DROP") = command.get(..4) {
+                        if !ui.drop_item() {
+                            quiet = true;
+                        }
+                        break;
+                    }
+
+                    if let Some("TA") = command.get(..2) {
+                        if !ui.take_item() {
+                            quiet = true;
+                        }
+                        break;
+                    }
+
                     if let Some("DR") = command.get(..2) {
                         ui.drink();
                         quiet = true;
                         break;
                     }
 
+                    if let Some("GO") = command.get(..2) {
+                        if ui.travel(command[2..].trim()) {
+                            ui.travel_step();
+                        } else {
+                            quiet = true;
+                        }
+                        break;
+                    }
+
                     match command.get(..1) {
                         Some("M") => {
-                            ui.map(false);
+                            ui.map();
                             print_stats = false;
                             resolve_room_effects = false;
                         }
@@ -1745,6 +3319,11 @@ fn main() {
                             print_stats = false;
                             resolve_room_effects = false;
                         }
+                        Some("C") => {
+                            if !ui.cast() {
+                                quiet = true;
+                            }
+                        }
                         Some("G") => {
                             if !ui.gaze() {
                                 quiet = true;
@@ -1761,7 +3340,22 @@ fn main() {
                                 alive = false;
                             }
                         }
-                        Some("H") | Some("?") => {
+                        Some("X") => {
+                            if !ui.explore(command[1..].trim()) || !ui.explore_step() {
+                                quiet = true;
+                            }
+                        }
+                        Some("B") => {
+                            if !ui.bank() {
+                                quiet = true;
+                            }
+                        }
+                        Some("I") => {
+                            ui.inventory();
+                            free_move = true;
+                            quiet = true;
+                        }
+                        Some("H") | Some("?") if ui.game.config().rules.help => {
                             ui.help();
                             free_move = true;
                             quiet = true;
@@ -1776,12 +3370,14 @@ fn main() {
 
             // See if the player walked out
             if ui.game.state() == GameState::Exit {
+                ui.check_splits();
                 alive = false;
                 continue;
             }
 
-            // See if we were killed by something (exploding chest)
-            if ui.game.state() == GameState::Dead {
+            // See if we were killed by something (exploding chest), or lost
+            // track of time (gas)
+            if matches!(ui.game.state(), GameState::Dead | GameState::OutOfTime) {
                 alive = false;
                 continue;
             }
@@ -1808,12 +3404,23 @@ fn main() {
             print_stats = true;
 
             if resolve_room_effects {
-                match ui.game.room_effect() {
+                let event = ui.game.room_effect();
+
+                // Anything happening in a room cuts a trip short
+                if !matches!(event, Event::None) {
+                    ui.stop_walking();
+                }
+
+                match event {
                     Event::FoundGold(_) => {
                         println!("\nYOU HAVE {}", ui.game.player_gp());
                     }
                     Event::FoundFlares(_) => {
-                        println!("\nYOU HAVE {}", ui.game.player_flares());
+                        if ui.left_on_floor(Item::Flare) {
+                            println!("\nYOUR PACK IS FULL, SO YOU LEAVE THEM ON THE FLOOR");
+                        } else {
+                            println!("\nYOU HAVE {}", ui.game.player_flares());
+                        }
                     }
                     Event::Sinkhole => {
                         automove = true;
@@ -1838,6 +3445,11 @@ fn main() {
 
             resolve_room_effects = true;
 
+            // Stop exploring at anything worth a closer look
+            if ui.exploring() && ui.game.player_view().here_needs_attention() {
+                ui.explore_steps = 0;
+            }
+
             // If we're chosen to fight the vendor, let's do that
             if ui.game.state() == GameState::VendorAttack {
                 automove = true;
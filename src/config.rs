@@ -0,0 +1,40 @@
+use crate::curse::CurseTable;
+use crate::difficulty::Difficulty;
+use crate::economy::Economy;
+use crate::monster::{MonsterTable, SpawnMode};
+use crate::ruleset::Ruleset;
+
+/// Options that are chosen before the game starts
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    pub spawn_mode: SpawnMode,
+    pub monsters: MonsterTable,
+    pub curses: CurseTable,
+    pub economy: Economy,
+    pub rules: Ruleset,
+    pub difficulty: Difficulty,
+    pub turn_limit: Option<u32>, // The game ends after this many turns
+    pub wear_chance: u32,        // 1 in this many melee hits wears the weapon
+
+    pub wandering: bool,    // Monsters move between rooms as turns pass
+    pub regeneration: bool, // Wounded monsters heal as turns pass
+    pub speedrun: bool,     // Time the game on the wall clock
+}
+
+impl Default for GameConfig {
+    fn default() -> GameConfig {
+        GameConfig {
+            spawn_mode: SpawnMode::Classic,
+            monsters: MonsterTable::classic(),
+            curses: CurseTable::classic(),
+            economy: Economy::classic(),
+            rules: Ruleset::modern(),
+            difficulty: Difficulty::Normal,
+            wandering: false,
+            regeneration: false,
+            turn_limit: None,
+            wear_chance: 3,
+            speedrun: false,
+        }
+    }
+}
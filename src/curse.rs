@@ -1,4 +1,6 @@
-pub const CURSE_COUNT: u32 = 3;
+use crate::data;
+use crate::error::Error;
+use crate::treasure::TreasureType;
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum CurseType {
@@ -6,17 +8,254 @@ pub enum CurseType {
     Forgetfulness,
     TheLeech,
     Lethargy,
+    Clumsiness,
+    Greed,
+    Darkness,
 }
 
-pub struct Curse {}
+impl CurseType {
+    /// Return the curse with a given name, ignoring case
+    fn from_name(name: &str) -> Option<CurseType> {
+        match name.to_uppercase().as_str() {
+            "FORGETFULNESS" => Some(CurseType::Forgetfulness),
+            "THE LEECH" => Some(CurseType::TheLeech),
+            "LETHARGY" => Some(CurseType::Lethargy),
+            "CLUMSINESS" => Some(CurseType::Clumsiness),
+            "GREED" => Some(CurseType::Greed),
+            "DARKNESS" => Some(CurseType::Darkness),
+            _ => None,
+        }
+    }
+}
+
+/// A way to lift a curse
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum CureType {
+    Vendor,
+    Pool,
+    Spell,
+}
+
+impl CureType {
+    /// Return the cure for a data file name
+    fn from_name(name: &str) -> Option<CureType> {
+        match name {
+            "vendor" => Some(CureType::Vendor),
+            "pool" => Some(CureType::Pool),
+            "spell" => Some(CureType::Spell),
+            _ => None,
+        }
+    }
+}
+
+/// How a kind of curse behaves
+#[derive(Debug, Clone)]
+pub struct CurseDef {
+    pub curse_type: CurseType,
+    pub rooms: u32,                 // Cursed rooms placed in the castle
+    pub duration: u32,              // Turns it lasts, 0 for until cured
+    pub max_stacks: u32,            // Most times it can be caught at once
+    pub ward: Option<TreasureType>, // Treasure that holds it off
+    pub cures: Vec<CureType>,
+}
+
+impl CurseDef {
+    /// True if the curse can be lifted a given way
+    pub fn cured_by(&self, cure: CureType) -> bool {
+        self.cures.contains(&cure)
+    }
+}
+
+/// All the curses in the game
+#[derive(Debug, Clone)]
+pub struct CurseTable {
+    defs: Vec<CurseDef>,
+}
+
+impl CurseTable {
+    /// Return the standard curses
+    pub fn classic() -> CurseTable {
+        CurseTable::parse(include_str!("../data/curses.txt"))
+            .expect("built-in curse data is broken")
+    }
+
+    /// Load curses from a data file
+    pub fn load(path: &str) -> Result<CurseTable, Error> {
+        CurseTable::parse(&data::read_file(path)?)
+    }
+
+    /// Parse curse data
+    ///
+    /// See `data/curses.txt` for the format.
+    pub fn parse(text: &str) -> Result<CurseTable, Error> {
+        let mut defs: Vec<CurseDef> = Vec::new();
+
+        for record in data::records(text) {
+            let bad = Error::BadData(record.line);
+
+            if record.fields.len() != 6 {
+                return Err(bad);
+            }
+
+            let curse_type = CurseType::from_name(&record.str(0)?.replace('_', " ")).ok_or(bad)?;
+
+            if defs.iter().any(|d| d.curse_type == curse_type) {
+                return Err(bad);
+            }
+
+            let ward = match record.str(4)? {
+                "-" => None,
+                name => Some(TreasureType::from_name(&name.replace('_', " ")).ok_or(bad)?),
+            };
+
+            let mut cures = Vec::new();
+
+            for c in record.list(5)? {
+                cures.push(CureType::from_name(c).ok_or(bad)?);
+            }
+
+            let max_stacks = record.u32(3)?;
+
+            if max_stacks == 0 {
+                return Err(bad);
+            }
+
+            defs.push(CurseDef {
+                curse_type,
+                rooms: record.u32(1)?,
+                duration: record.u32(2)?,
+                max_stacks,
+                ward,
+                cures,
+            });
+        }
+
+        Ok(CurseTable { defs })
+    }
+
+    /// Return the definition for a curse type
+    pub fn get(&self, curse_type: CurseType) -> Option<&CurseDef> {
+        self.defs.iter().find(|d| d.curse_type == curse_type)
+    }
+
+    /// Return every curse definition
+    pub fn defs(&self) -> &[CurseDef] {
+        &self.defs
+    }
+}
+
+impl Default for CurseTable {
+    fn default() -> CurseTable {
+        CurseTable::classic()
+    }
+}
+
+/// A curse on the player
+#[derive(Debug, Copy, Clone)]
+pub struct Curse {
+    pub curse_type: CurseType,
+    pub stacks: u32,
+    pub turns_left: Option<u32>, // None if it lasts until cured
+}
 
 impl Curse {
-    pub fn get_curse_by_id(id: u32) -> CurseType {
-        match id {
-            0 => CurseType::Forgetfulness,
-            1 => CurseType::TheLeech,
-            2 => CurseType::Lethargy,
-            _ => panic!("get_curse_by_id: unknown id"),
+    /// Catch a new curse
+    pub fn new(def: &CurseDef) -> Curse {
+        Curse {
+            curse_type: def.curse_type,
+            stacks: 1,
+            turns_left: Curse::duration(def),
+        }
+    }
+
+    /// Catch the same curse again, making it worse and starting its clock
+    /// over
+    pub fn stack(&mut self, def: &CurseDef) {
+        self.stacks = std::cmp::min(self.stacks + 1, def.max_stacks);
+        self.turns_left = Curse::duration(def);
+    }
+
+    /// Let a turn pass
+    ///
+    /// Returns true if the curse has run its course
+    pub fn tick(&mut self) -> bool {
+        match self.turns_left {
+            Some(ref mut turns) => {
+                *turns = turns.saturating_sub(1);
+                *turns == 0
+            }
+            None => false,
+        }
+    }
+
+    /// Return how long a new curse lasts
+    fn duration(def: &CurseDef) -> Option<u32> {
+        match def.duration {
+            0 => None,
+            turns => Some(turns),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_data_keeps_original_curses() {
+        let table = CurseTable::classic();
+
+        assert_eq!(table.defs().len(), 6);
+
+        // The three original curses each had one room, lasted until cured,
+        // and were held off by a treasure
+        let original = [
+            (CurseType::Forgetfulness, TreasureType::GreenGem),
+            (CurseType::TheLeech, TreasureType::PalePearl),
+            (CurseType::Lethargy, TreasureType::RubyRed),
+        ];
+
+        for (curse_type, ward) in original {
+            let def = table.get(curse_type).unwrap();
+
+            assert_eq!(def.rooms, 1, "{:?}", curse_type);
+            assert_eq!(def.duration, 0, "{:?}", curse_type);
+            assert_eq!(def.ward, Some(ward), "{:?}", curse_type);
+            assert!(def.cured_by(CureType::Spell), "{:?}", curse_type);
+        }
+    }
+
+    #[test]
+    fn parse_curse() {
+        let table = CurseTable::parse("the_leech 2 50 3 Pale_Pearl pool,vendor\n").unwrap();
+        let def = table.get(CurseType::TheLeech).unwrap();
+
+        assert_eq!((def.rooms, def.duration, def.max_stacks), (2, 50, 3));
+        assert_eq!(def.ward, Some(TreasureType::PalePearl));
+        assert_eq!(def.cures, vec![CureType::Pool, CureType::Vendor]);
+        assert!(table.get(CurseType::Greed).is_none());
+    }
+
+    #[test]
+    fn parse_rejects_bad_lines() {
+        let bad = [
+            ("Greed 1 100 1 -\n", 1),                             // Missing cures
+            ("Greed 1 100 1 - vendor extra\n", 1),                // Too many fields
+            ("Gluttony 1 100 1 - vendor\n", 1),                   // Unknown curse
+            ("Greed one 100 1 - vendor\n", 1),                    // Not a number
+            ("Greed 1 100 0 - vendor\n", 1),                      // No stacks
+            ("Greed 1 100 1 Lump_of_Coal vendor\n", 1),           // Unknown ward
+            ("Greed 1 100 1 - prayer\n", 1),                      // Unknown cure
+            ("Greed 1 100 1 - -\n# again\nGreed 1 1 1 - -\n", 3), // Same curse twice
+        ];
+
+        for (text, line) in bad {
+            assert_eq!(
+                CurseTable::parse(text).err(),
+                Some(Error::BadData(line)),
+                "{}",
+                text
+            );
         }
     }
 }
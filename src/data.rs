@@ -0,0 +1,177 @@
+use std::fs;
+
+use crate::error::Error;
+
+/// A line from a data file, split into fields
+pub struct Record<'a> {
+    pub line: u32,
+    pub fields: Vec<&'a str>,
+}
+
+impl<'a> Record<'a> {
+    /// Return a field as a string
+    pub fn str(&self, i: usize) -> Result<&'a str, Error> {
+        self.fields.get(i).copied().ok_or(Error::BadData(self.line))
+    }
+
+    /// Return a field as a number
+    pub fn u32(&self, i: usize) -> Result<u32, Error> {
+        self.str(i)?.parse().map_err(|_| Error::BadData(self.line))
+    }
+
+    /// Return a comma-separated field as a list, where `-` is an empty list
+    pub fn list(&self, i: usize) -> Result<Vec<&'a str>, Error> {
+        match self.str(i)? {
+            "-" => Ok(Vec::new()),
+            s => Ok(s.split(',').collect()),
+        }
+    }
+}
+
+/// Read a data file into a string
+pub fn read_file(path: &str) -> Result<String, Error> {
+    fs::read_to_string(path).map_err(|_| Error::DataFile)
+}
+
+/// Split data file text into records of whitespace-separated fields
+///
+/// Everything after a `#` is a comment, and blank lines are skipped.
+pub fn records(text: &str) -> Vec<Record<'_>> {
+    text.lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line_num = i as u32 + 1;
+            let content = line.split('#').next().unwrap_or("");
+            let fields: Vec<&str> = content.split_whitespace().collect();
+
+            if fields.is_empty() {
+                None
+            } else {
+                Some(Record {
+                    line: line_num,
+                    fields,
+                })
+            }
+        })
+        .collect()
+}
+
+/// A `key = value` setting from a settings file
+pub struct Setting<'a> {
+    pub line: u32,
+    pub section: &'a str,
+    pub key: &'a str,
+    pub value: &'a str,
+}
+
+impl Setting<'_> {
+    /// Return the value as a number
+    pub fn u32(&self) -> Result<u32, Error> {
+        self.value.parse().map_err(|_| Error::BadData(self.line))
+    }
+
+    /// Return the value as a yes or no
+    pub fn bool(&self) -> Result<bool, Error> {
+        match self.value {
+            "yes" => Ok(true),
+            "no" => Ok(false),
+            _ => Err(Error::BadData(self.line)),
+        }
+    }
+}
+
+/// Split settings file text into `key = value` settings
+///
+/// A line with a name in square brackets starts a new section, and settings
+/// before the first section have an empty section name. Everything after a
+/// `#` is a comment, and blank lines are skipped.
+pub fn settings(text: &str) -> Result<Vec<Setting<'_>>, Error> {
+    let mut section = "";
+    let mut settings = Vec::new();
+
+    for (i, line) in text.lines().enumerate() {
+        let line_num = i as u32 + 1;
+        let content = line.split('#').next().unwrap_or("").trim();
+
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = content.strip_prefix('[').and_then(|c| c.strip_suffix(']')) {
+            section = name.trim();
+            continue;
+        }
+
+        match content.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() && !value.trim().is_empty() => settings
+                .push(Setting {
+                    line: line_num,
+                    section,
+                    key: key.trim(),
+                    value: value.trim(),
+                }),
+            _ => return Err(Error::BadData(line_num)),
+        }
+    }
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_skip_comments_and_blank_lines() {
+        let text = "# header\n\nKobold  A  3 # trailing\n   \nOrc AN 4\n";
+        let records = records(text);
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].line, 3);
+        assert_eq!(records[0].fields, vec!["Kobold", "A", "3"]);
+        assert_eq!(records[1].line, 5);
+    }
+
+    #[test]
+    fn record_fields() {
+        let text = "Wolf 5 pack,fire -";
+        let record = &records(text)[0];
+
+        assert_eq!(record.str(0), Ok("Wolf"));
+        assert_eq!(record.u32(1), Ok(5));
+        assert_eq!(record.u32(0), Err(Error::BadData(1)));
+        assert_eq!(record.list(2), Ok(vec!["pack", "fire"]));
+        assert_eq!(record.list(3), Ok(Vec::new()));
+        assert_eq!(record.str(4), Err(Error::BadData(1)));
+    }
+
+    #[test]
+    fn settings_track_sections() {
+        let text = "top = 1\n[one]\na = yes # comment\n\n[ two ]\nb=2\n";
+        let settings = settings(text).unwrap();
+
+        assert_eq!(settings.len(), 3);
+
+        assert_eq!(settings[0].section, "");
+        assert_eq!(settings[0].key, "top");
+
+        assert_eq!(settings[1].section, "one");
+        assert_eq!(settings[1].bool(), Ok(true));
+
+        assert_eq!(settings[2].section, "two");
+        assert_eq!(settings[2].line, 6);
+        assert_eq!(settings[2].u32(), Ok(2));
+    }
+
+    #[test]
+    fn settings_reject_malformed_lines() {
+        assert_eq!(settings("[a]\nno equals").err(), Some(Error::BadData(2)));
+        assert_eq!(settings("= 1").err(), Some(Error::BadData(1)));
+        assert_eq!(settings("a =").err(), Some(Error::BadData(1)));
+
+        let settings = settings("a = maybe\nb = -1").unwrap();
+
+        assert_eq!(settings[0].bool(), Err(Error::BadData(1)));
+        assert_eq!(settings[1].u32(), Err(Error::BadData(2)));
+    }
+}
@@ -0,0 +1,154 @@
+/// How hard the castle is on the player
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    /// Return the difficulty with a given name
+    pub fn from_name(name: &str) -> Option<Difficulty> {
+        match name {
+            "easy" => Some(Difficulty::Easy),
+            "normal" => Some(Difficulty::Normal),
+            "hard" => Some(Difficulty::Hard),
+            "nightmare" => Some(Difficulty::Nightmare),
+            _ => None,
+        }
+    }
+
+    /// Return the gold the player starts with
+    pub fn starting_gp(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 100,
+            Difficulty::Normal => 60,
+            Difficulty::Hard => 40,
+            Difficulty::Nightmare => 20,
+        }
+    }
+
+    /// Return the bonus points to hand out to stats, given the usual number
+    pub fn bonus_points(&self, usual: u32) -> u32 {
+        match self {
+            Difficulty::Easy => usual + 4,
+            Difficulty::Normal => usual,
+            Difficulty::Hard => usual.saturating_sub(2),
+            Difficulty::Nightmare => usual.saturating_sub(4),
+        }
+    }
+
+    /// Return the percent of their usual hit points and damage monsters get
+    pub fn monster_percent(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 75,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 125,
+            Difficulty::Nightmare => 150,
+        }
+    }
+
+    /// Return what's added to the player's dexterity when hitting and dodging
+    pub fn hit_bonus(&self) -> i32 {
+        match self {
+            Difficulty::Easy => 2,
+            Difficulty::Normal => 0,
+            Difficulty::Hard => -1,
+            Difficulty::Nightmare => -3,
+        }
+    }
+
+    /// Return the percent chance a pool's fate is rolled again
+    ///
+    /// Positive chances give a harmful drink another roll, and negative ones
+    /// give a helpful drink another roll.
+    pub fn pool_luck(&self) -> i32 {
+        match self {
+            Difficulty::Easy => 50,
+            Difficulty::Normal => 0,
+            Difficulty::Hard => -25,
+            Difficulty::Nightmare => -50,
+        }
+    }
+
+    /// Return the percent of their usual prices vendors charge
+    pub fn price_percent(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 80,
+            Difficulty::Normal => 100,
+            Difficulty::Hard => 120,
+            Difficulty::Nightmare => 150,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Difficulty; 4] = [
+        Difficulty::Easy,
+        Difficulty::Normal,
+        Difficulty::Hard,
+        Difficulty::Nightmare,
+    ];
+
+    #[test]
+    fn names() {
+        let names = ["easy", "normal", "hard", "nightmare"];
+
+        for (name, difficulty) in names.iter().zip(ALL) {
+            assert_eq!(Difficulty::from_name(name), Some(difficulty));
+        }
+
+        assert_eq!(Difficulty::from_name("Normal"), None);
+        assert_eq!(Difficulty::from_name("impossible"), None);
+    }
+
+    #[test]
+    fn normal_plays_like_the_original() {
+        let normal = Difficulty::Normal;
+
+        assert_eq!(normal.starting_gp(), 60);
+        assert_eq!(normal.bonus_points(8), 8);
+        assert_eq!(normal.bonus_points(4), 4);
+        assert_eq!(normal.monster_percent(), 100);
+        assert_eq!(normal.hit_bonus(), 0);
+        assert_eq!(normal.pool_luck(), 0);
+        assert_eq!(normal.price_percent(), 100);
+    }
+
+    #[test]
+    fn every_knob_gets_harder_in_order() {
+        for pair in ALL.windows(2) {
+            let (easier, harder) = (pair[0], pair[1]);
+
+            assert!(easier.starting_gp() > harder.starting_gp(), "{:?}", harder);
+            assert!(
+                easier.bonus_points(8) > harder.bonus_points(8),
+                "{:?}",
+                harder
+            );
+            assert!(
+                easier.monster_percent() < harder.monster_percent(),
+                "{:?}",
+                harder
+            );
+            assert!(easier.hit_bonus() > harder.hit_bonus(), "{:?}", harder);
+            assert!(easier.pool_luck() > harder.pool_luck(), "{:?}", harder);
+            assert!(
+                easier.price_percent() < harder.price_percent(),
+                "{:?}",
+                harder
+            );
+        }
+    }
+
+    #[test]
+    fn bonus_points_never_go_below_zero() {
+        assert_eq!(Difficulty::Hard.bonus_points(1), 0);
+        assert_eq!(Difficulty::Nightmare.bonus_points(4), 0);
+        assert_eq!(Difficulty::Easy.bonus_points(0), 4);
+    }
+}
@@ -4,9 +4,10 @@ use self::rand::seq::SliceRandom;
 use self::rand::rng;
 use self::rand::Rng;
 
-use crate::curse::Curse;
-use crate::monster::{Monster, MonsterType};
+use crate::config::GameConfig;
+use crate::monster::{Monster, MonsterDef, SpawnMode};
 use crate::room::{Room, RoomType};
+use crate::stats::Stats;
 use crate::treasure::Treasure;
 
 #[derive(Debug)]
@@ -20,7 +21,7 @@ pub struct Dungeon {
 }
 
 impl Dungeon {
-    pub fn new(xsize: u32, ysize: u32, zsize: u32) -> Dungeon {
+    pub fn new(xsize: u32, ysize: u32, zsize: u32, config: &GameConfig) -> Dungeon {
         let mut levels: Vec<Vec<Room>> = Vec::new();
 
         let area = xsize * ysize;
@@ -36,13 +37,20 @@ impl Dungeon {
 
             Dungeon::place_ent_stairs(&mut this_level, z, zsize, area);
             Dungeon::place_items(&mut this_level, orb_of_zot_level, z, area);
-            Dungeon::place_monsters_vendors(&mut this_level, z, area, runestaff_level);
+            Dungeon::place_monsters_vendors(
+                &mut this_level,
+                z,
+                zsize,
+                area,
+                runestaff_level,
+                config,
+            );
 
             levels.push(this_level);
         }
 
         // Curses and treasures
-        Dungeon::place_curse_treasure(&mut levels, zsize);
+        Dungeon::place_curse_treasure(&mut levels, zsize, config);
 
         // Run through the levels, padding them with empty rooms, shuffling
         // them, and moving certain rooms to their proper positions.
@@ -200,67 +208,128 @@ impl Dungeon {
     }
 
     /// Place monsters and vendors in the dungeon
-    fn place_monsters_vendors(this_level: &mut Vec<Room>, z: u32, area: u32, runestaff_level: u32) {
+    fn place_monsters_vendors(
+        this_level: &mut Vec<Room>,
+        z: u32,
+        zsize: u32,
+        area: u32,
+        runestaff_level: u32,
+        config: &GameConfig,
+    ) {
         let vendor_count = area / 21; // 3 in 8x8
         let monster_count = area / 5; // 12 in 8x8
 
         let mut rng = rng();
 
-        // Monsters
-        let monsters_to_place = [
-            MonsterType::Kobold,
-            MonsterType::Orc,
-            MonsterType::Wolf,
-            MonsterType::Goblin,
-            MonsterType::Ogre,
-            MonsterType::Troll,
-            MonsterType::Bear,
-            MonsterType::Minotaur,
-            MonsterType::Gargoyle,
-            MonsterType::Chimera,
-            MonsterType::Balrog,
-            MonsterType::Dragon,
-            // Not counting Vendors
-        ];
-
-        let num_monsters = monsters_to_place.len();
+        // Monsters, not counting vendors
+        let spawnable = config.monsters.spawnable();
+
+        // Classic placement cycles through this, with each monster appearing
+        // as many times as its spawn weight
+        let monsters_to_place: Vec<&MonsterDef> = spawnable
+            .iter()
+            .flat_map(|&def| std::iter::repeat_n(def, def.spawn_weight as usize))
+            .collect();
 
         let monster_with_runestaff = rng.random_range(0..monster_count) as usize;
 
         for i in 0..monster_count as usize {
             let has_runestaff = i == monster_with_runestaff && z == runestaff_level;
 
-            let m_num = i % num_monsters;
+            let mut monster = match config.spawn_mode {
+                SpawnMode::Classic => {
+                    let m_num = i % monsters_to_place.len();
+
+                    Monster::new(monsters_to_place[m_num], has_runestaff)
+                }
+                SpawnMode::DepthScaled => {
+                    let m_num = Dungeon::spawn_pick(&spawnable, z, zsize);
+
+                    Monster::new_at_depth(spawnable[m_num], has_runestaff, z, zsize)
+                }
+            };
+
+            monster.scale(config.difficulty.monster_percent());
 
             this_level.push(Room {
-                roomtype: RoomType::Monster(Monster::new(monsters_to_place[m_num], has_runestaff)),
+                roomtype: RoomType::Monster(monster),
                 ..Default::default()
             });
         }
 
         // Vendors
+        let vendor = config
+            .monsters
+            .vendor()
+            .expect("monster table has no vendor");
+
         for _ in 0..vendor_count {
+            let mut monster = Monster::new_vendor(vendor, z, zsize, &config.economy);
+
+            monster.scale(config.difficulty.monster_percent());
+
             this_level.push(Room {
-                roomtype: RoomType::Monster(Monster::new(MonsterType::Vendor, false)),
+                roomtype: RoomType::Monster(monster),
                 ..Default::default()
             });
         }
     }
 
+    /// Return the spawn weight for a monster at a given depth
+    ///
+    /// Monsters are ranked weakest to strongest. Each level has a target rank
+    /// that moves from the weakest monster on the top level to the strongest
+    /// on the bottom level, and the weight falls off with distance from that
+    /// target. Every monster keeps a weight of at least 1 so that anything can
+    /// turn up anywhere.
+    fn spawn_weight(monster_num: u32, num_monsters: u32, z: u32, zsize: u32) -> u32 {
+        let max_z = std::cmp::max(zsize, 2) - 1;
+        let target = z * (num_monsters - 1) / max_z;
+
+        let distance = monster_num.abs_diff(target);
+
+        1 + num_monsters - std::cmp::min(distance * 2, num_monsters)
+    }
+
+    /// Choose a random monster number weighted by the spawn table for a level
+    ///
+    /// The depth weight is multiplied by each monster's own spawn weight.
+    fn spawn_pick(spawnable: &[&MonsterDef], z: u32, zsize: u32) -> usize {
+        let num_monsters = spawnable.len() as u32;
+
+        let weight = |m: usize| {
+            spawnable[m].spawn_weight * Dungeon::spawn_weight(m as u32, num_monsters, z, zsize)
+        };
+
+        let total: u32 = (0..spawnable.len()).map(weight).sum();
+
+        let mut roll = rng().random_range(0..total);
+
+        for m in 0..spawnable.len() {
+            if roll < weight(m) {
+                return m;
+            }
+
+            roll -= weight(m);
+        }
+
+        panic!("spawn_pick: roll out of range");
+    }
+
     /// Place curses and treasures
-    fn place_curse_treasure(levels: &mut [Vec<Room>], zsize: u32) {
+    fn place_curse_treasure(levels: &mut [Vec<Room>], zsize: u32, config: &GameConfig) {
         let mut rng = rng();
 
         // Add curse rooms
-        for i in 0..crate::curse::CURSE_COUNT {
-            let curse_level = rng.random_range(0..zsize) as usize;
-
-            let curse = Curse::get_curse_by_id(i);
+        for def in config.curses.defs() {
+            for _ in 0..def.rooms {
+                let curse_level = rng.random_range(0..zsize) as usize;
 
-            levels[curse_level].push(Room {
-                curse,
-                ..Default::default()
-            })
+                levels[curse_level].push(Room {
+                    curse: def.curse_type,
+                    ..Default::default()
+                })
+            }
         }
 
         // Add treasures
@@ -298,14 +367,14 @@ impl Dungeon {
                 let mut downs = Vec::new();
                 let mut ups = Vec::new();
 
-                for i in 0..area as usize {
-                    if levels[z - 1][i].roomtype == RoomType::StairsDown {
+                for (i, room) in levels[z - 1].iter().enumerate().take(area as usize) {
+                    if room.roomtype == RoomType::StairsDown {
                         downs.push(i);
                     }
                 }
 
-                for i in 0..area as usize {
-                    if levels[z][i].roomtype == RoomType::StairsUp {
+                for (i, room) in levels[z].iter().enumerate().take(area as usize) {
+                    if room.roomtype == RoomType::StairsUp {
                         ups.push(i);
                     }
                 }
@@ -319,6 +388,63 @@ impl Dungeon {
         }
     }
 
+    /// Return exploration and content counts for a level
+    pub fn level_stats(&self, z: u32) -> Stats {
+        let mut stats = Stats::default();
+
+        let level = &self.levels[z as usize];
+
+        for room in level {
+            stats.rooms += 1;
+
+            if room.discovered {
+                stats.discovered += 1;
+            }
+
+            if room.cleared {
+                stats.cleared += 1;
+            }
+
+            match room.roomtype {
+                RoomType::Monster(ref m) => {
+                    if m.is_vendor() {
+                        stats.vendors += 1;
+                    } else {
+                        stats.monsters += 1;
+                    }
+                }
+                RoomType::Treasure(_) => stats.treasures += 1,
+                RoomType::Gold
+                | RoomType::Pool
+                | RoomType::Chest
+                | RoomType::Flares
+                | RoomType::CrystalOrb
+                | RoomType::Book => stats.items += 1,
+                RoomType::StairsUp | RoomType::StairsDown => {
+                    stats.stairs += 1;
+
+                    if room.discovered {
+                        stats.stairs_found += 1;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        stats
+    }
+
+    /// Return exploration and content counts for the whole castle
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats::default();
+
+        for z in 0..self.zsize {
+            stats += self.level_stats(z);
+        }
+
+        stats
+    }
+
     /// Get the entrance x position
     pub fn entrance_x(&self) -> u32 {
         (self.xsize - 1) / 2
@@ -327,7 +453,6 @@ impl Dungeon {
     /// Return a reference to the room at a location
     pub fn room_at(&self, x: u32, y: u32, z: u32) -> &Room {
         // TODO: Result
-        // TODO modify this so that it returns unknown for undiscovered rooms
         let i = y * self.xsize + x;
 
         &self.levels[z as usize][i as usize]
@@ -341,6 +466,25 @@ impl Dungeon {
         &mut self.levels[z as usize][i as usize]
     }
 
+    /// Return all the monsters in the castle
+    pub fn monsters_mut(&mut self) -> impl Iterator<Item = &mut Monster> {
+        self.levels
+            .iter_mut()
+            .flatten()
+            .filter_map(|room| match room.roomtype {
+                RoomType::Monster(ref mut m) => Some(m),
+                _ => None,
+            })
+    }
+
+    /// Return the monster in a room, if there is one
+    pub fn monster_at_mut(&mut self, x: u32, y: u32, z: u32) -> Option<&mut Monster> {
+        match self.room_at_mut(x, y, z).roomtype {
+            RoomType::Monster(ref mut m) => Some(m),
+            _ => None,
+        }
+    }
+
     /// Discover a room
     pub fn discover(&mut self, x: u32, y: u32, z: u32) {
         let i = y * self.xsize + x;
@@ -372,4 +516,77 @@ impl Dungeon {
     pub fn runestaff_location(&self) -> (u32, u32, u32) {
         self.runestaff
     }
+
+    /// Move a monster from one room to another on the same level
+    ///
+    /// The room it leaves becomes empty, and whatever was in the room it
+    /// enters is lost, so callers should only move monsters into empty rooms.
+    pub fn move_monster(&mut self, from: (u32, u32), to: (u32, u32), z: u32) {
+        let roomtype = std::mem::replace(
+            &mut self.room_at_mut(from.0, from.1, z).roomtype,
+            RoomType::Empty,
+        );
+
+        if let RoomType::Monster(ref m) = roomtype {
+            if m.has_runestaff() {
+                self.runestaff = (to.0, to.1, z);
+            }
+        }
+
+        self.room_at_mut(to.0, to.1, z).roomtype = roomtype;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monster::MonsterTable;
+
+    #[test]
+    fn spawn_weights_follow_depth() {
+        // The top level favors the weakest, the bottom level the strongest
+        assert_eq!(Dungeon::spawn_weight(0, 12, 0, 8), 13);
+        assert_eq!(Dungeon::spawn_weight(11, 12, 0, 8), 1);
+        assert_eq!(Dungeon::spawn_weight(0, 12, 7, 8), 1);
+        assert_eq!(Dungeon::spawn_weight(11, 12, 7, 8), 13);
+
+        // Weight falls off on both sides of a level's target
+        let target = 4 * 11 / 7;
+
+        assert_eq!(Dungeon::spawn_weight(target, 12, 4, 8), 13);
+        assert_eq!(Dungeon::spawn_weight(target - 1, 12, 4, 8), 11);
+        assert_eq!(Dungeon::spawn_weight(target + 1, 12, 4, 8), 11);
+    }
+
+    #[test]
+    fn every_monster_can_turn_up_anywhere() {
+        for zsize in 1..10 {
+            for z in 0..zsize {
+                for m in 0..12 {
+                    assert!(Dungeon::spawn_weight(m, 12, z, zsize) >= 1);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn spawn_pick_honors_monster_weights() {
+        let mut defs: Vec<MonsterDef> = MonsterTable::classic()
+            .spawnable()
+            .into_iter()
+            .cloned()
+            .collect();
+
+        for def in defs.iter_mut() {
+            def.spawn_weight = 0;
+        }
+
+        defs[3].spawn_weight = 1;
+
+        let spawnable: Vec<&MonsterDef> = defs.iter().collect();
+
+        for z in 0..8 {
+            assert_eq!(Dungeon::spawn_pick(&spawnable, z, 8), 3);
+        }
+    }
 }
@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+
+use crate::armor::ArmorType;
+use crate::data;
+use crate::equipment::Equipment;
+use crate::error::Error;
+use crate::inventory::Item;
+use crate::ring::RingType;
+use crate::shield::ShieldType;
+use crate::stock::Ware;
+use crate::treasure::{Treasure, TreasureType};
+use crate::weapon::WeaponType;
+
+/// Something in the game with a price
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum Price {
+    StartLeather,
+    StartChainmail,
+    StartPlate,
+    StartDagger,
+    StartMace,
+    StartSword,
+    StartLamp,
+    Flare,
+    VendorLeather,
+    VendorChainmail,
+    VendorPlate,
+    VendorDagger,
+    VendorMace,
+    VendorSword,
+    Buckler,
+    KiteShield,
+    TowerShield,
+    RingOfProtection,
+    RingOfAccuracy,
+    RingOfRegeneration,
+    Enchantment,
+    Potion,
+    VendorLamp,
+    Repair,
+    Cure,
+    TreasureValue,
+    VendorFlare,
+    HealingPotion,
+    SightElixir,
+    MappingScroll,
+    Solvent,
+    Bribe,
+}
+
+/// Every price in the game
+pub const PRICES: [Price; 32] = [
+    Price::StartLeather,
+    Price::StartChainmail,
+    Price::StartPlate,
+    Price::StartDagger,
+    Price::StartMace,
+    Price::StartSword,
+    Price::StartLamp,
+    Price::Flare,
+    Price::VendorLeather,
+    Price::VendorChainmail,
+    Price::VendorPlate,
+    Price::VendorDagger,
+    Price::VendorMace,
+    Price::VendorSword,
+    Price::Buckler,
+    Price::KiteShield,
+    Price::TowerShield,
+    Price::RingOfProtection,
+    Price::RingOfAccuracy,
+    Price::RingOfRegeneration,
+    Price::Enchantment,
+    Price::Potion,
+    Price::VendorLamp,
+    Price::Repair,
+    Price::Cure,
+    Price::TreasureValue,
+    Price::VendorFlare,
+    Price::HealingPotion,
+    Price::SightElixir,
+    Price::MappingScroll,
+    Price::Solvent,
+    Price::Bribe,
+];
+
+impl Price {
+    /// Return the name of the price in an economy file
+    pub fn key(&self) -> &'static str {
+        match self {
+            Price::StartLeather => "start_leather",
+            Price::StartChainmail => "start_chainmail",
+            Price::StartPlate => "start_plate",
+            Price::StartDagger => "start_dagger",
+            Price::StartMace => "start_mace",
+            Price::StartSword => "start_sword",
+            Price::StartLamp => "start_lamp",
+            Price::Flare => "flare",
+            Price::VendorLeather => "vendor_leather",
+            Price::VendorChainmail => "vendor_chainmail",
+            Price::VendorPlate => "vendor_plate",
+            Price::VendorDagger => "vendor_dagger",
+            Price::VendorMace => "vendor_mace",
+            Price::VendorSword => "vendor_sword",
+            Price::Buckler => "buckler",
+            Price::KiteShield => "kite_shield",
+            Price::TowerShield => "tower_shield",
+            Price::RingOfProtection => "ring_of_protection",
+            Price::RingOfAccuracy => "ring_of_accuracy",
+            Price::RingOfRegeneration => "ring_of_regeneration",
+            Price::Enchantment => "enchantment",
+            Price::Potion => "potion",
+            Price::VendorLamp => "vendor_lamp",
+            Price::Repair => "repair",
+            Price::Cure => "cure",
+            Price::TreasureValue => "treasure_value",
+            Price::VendorFlare => "vendor_flare",
+            Price::HealingPotion => "healing_potion",
+            Price::SightElixir => "sight_elixir",
+            Price::MappingScroll => "mapping_scroll",
+            Price::Solvent => "solvent",
+            Price::Bribe => "bribe",
+        }
+    }
+
+    /// Return the price with a given name in an economy file
+    fn from_key(key: &str) -> Option<Price> {
+        PRICES.into_iter().find(|p| p.key() == key)
+    }
+}
+
+/// Prices, and how they change deeper in the castle
+#[derive(Debug, Clone)]
+pub struct Economy {
+    prices: HashMap<Price, u32>,
+    vendor_depth: u32,   // Percent vendor prices rise per level
+    treasure_depth: u32, // Percent treasure values rise per level
+}
+
+impl Economy {
+    /// Return the classic prices
+    pub fn classic() -> Economy {
+        let empty = Economy {
+            prices: HashMap::new(),
+            vendor_depth: 0,
+            treasure_depth: 0,
+        };
+
+        let economy = empty
+            .apply(include_str!("../data/economy.txt"))
+            .expect("built-in economy data is broken");
+
+        assert!(
+            PRICES.iter().all(|p| economy.prices.contains_key(p)),
+            "built-in economy data is missing prices"
+        );
+
+        economy
+    }
+
+    /// Load prices from a data file
+    pub fn load(path: &str) -> Result<Economy, Error> {
+        Economy::parse(&data::read_file(path)?)
+    }
+
+    /// Parse economy data
+    ///
+    /// See `data/economy.txt` for the format. Anything the text leaves out
+    /// keeps its classic price.
+    pub fn parse(text: &str) -> Result<Economy, Error> {
+        Economy::classic().apply(text)
+    }
+
+    /// Return a copy of these prices with the settings in a data file applied
+    ///
+    /// This lets a rules file carry an `[economy]` section alongside its
+    /// `[rules]`.
+    pub fn apply_file(self, path: &str) -> Result<Economy, Error> {
+        self.apply(&data::read_file(path)?)
+    }
+
+    /// Return a copy of these prices with the settings in some text applied
+    ///
+    /// A `[rules]` section is left for `Ruleset` to read.
+    fn apply(mut self, text: &str) -> Result<Economy, Error> {
+        for setting in data::settings(text)? {
+            match setting.section {
+                "economy" => (),
+                "rules" => continue,
+                _ => return Err(Error::BadData(setting.line)),
+            }
+
+            let value = setting.u32()?;
+
+            match setting.key {
+                "vendor_depth" => self.vendor_depth = value,
+                "treasure_depth" => self.treasure_depth = value,
+                key => {
+                    let price = Price::from_key(key).ok_or(Error::BadData(setting.line))?;
+
+                    // Treasures are rolled from 1 up to this, so it can't be zero
+                    if matches!(price, Price::TreasureValue) && value == 0 {
+                        return Err(Error::BadData(setting.line));
+                    }
+
+                    self.prices.insert(price, value);
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Return a price
+    pub fn get(&self, price: Price) -> u32 {
+        self.prices.get(&price).copied().unwrap_or(0)
+    }
+
+    /// Return the cost of armor, either before the game or from a vendor
+    pub fn armor_cost(&self, a: ArmorType, is_vendor: bool) -> u32 {
+        let price = match (a, is_vendor) {
+            (ArmorType::None, _) => return 0,
+            (ArmorType::Leather, false) => Price::StartLeather,
+            (ArmorType::Chainmail, false) => Price::StartChainmail,
+            (ArmorType::Plate, false) => Price::StartPlate,
+            (ArmorType::Leather, true) => Price::VendorLeather,
+            (ArmorType::Chainmail, true) => Price::VendorChainmail,
+            (ArmorType::Plate, true) => Price::VendorPlate,
+        };
+
+        self.get(price)
+    }
+
+    /// Return the cost of a weapon, either before the game or from a vendor
+    pub fn weapon_cost(&self, w: WeaponType, is_vendor: bool) -> u32 {
+        let price = match (w, is_vendor) {
+            (WeaponType::None, _) => return 0,
+            (WeaponType::Dagger, false) => Price::StartDagger,
+            (WeaponType::Mace, false) => Price::StartMace,
+            (WeaponType::Sword, false) => Price::StartSword,
+            (WeaponType::Dagger, true) => Price::VendorDagger,
+            (WeaponType::Mace, true) => Price::VendorMace,
+            (WeaponType::Sword, true) => Price::VendorSword,
+        };
+
+        self.get(price)
+    }
+
+    /// Return the cost of a shield from a vendor
+    pub fn shield_cost(&self, s: ShieldType) -> u32 {
+        match s {
+            ShieldType::None => 0,
+            ShieldType::Buckler => self.get(Price::Buckler),
+            ShieldType::Kite => self.get(Price::KiteShield),
+            ShieldType::Tower => self.get(Price::TowerShield),
+        }
+    }
+
+    /// Return the cost of a ring from a vendor
+    pub fn ring_cost(&self, r: RingType) -> u32 {
+        match r {
+            RingType::None => 0,
+            RingType::Protection => self.get(Price::RingOfProtection),
+            RingType::Accuracy => self.get(Price::RingOfAccuracy),
+            RingType::Regeneration => self.get(Price::RingOfRegeneration),
+        }
+    }
+
+    /// Return the cost of equipment from a vendor
+    pub fn equipment_cost(&self, e: Equipment) -> u32 {
+        match e {
+            Equipment::Shield(s) => self.shield_cost(s),
+            Equipment::Ring(r) => self.ring_cost(r),
+            Equipment::EnchantedWeapon(w, bonus) => {
+                self.weapon_cost(w, true) + bonus * self.get(Price::Enchantment)
+            }
+            Equipment::ElvenArmor(a) => self.armor_cost(a, true) + self.get(Price::Enchantment),
+        }
+    }
+
+    /// Return the cost of a ware from a vendor on the top level
+    pub fn ware_cost(&self, ware: Ware) -> u32 {
+        match ware {
+            Ware::Armor(a) => self.armor_cost(a, true),
+            Ware::Weapon(w) => self.weapon_cost(w, true),
+            Ware::Equipment(e) => self.equipment_cost(e),
+            Ware::Potion(_) => self.get(Price::Potion),
+            Ware::Lamp => self.get(Price::VendorLamp),
+            Ware::Supply(item) => self.supply_cost(item),
+        }
+    }
+
+    /// Return the cost of one consumable item from a vendor
+    pub fn supply_cost(&self, item: Item) -> u32 {
+        match item {
+            Item::Flare => self.get(Price::VendorFlare),
+            Item::HealingPotion => self.get(Price::HealingPotion),
+            Item::SightElixir => self.get(Price::SightElixir),
+            Item::MappingScroll => self.get(Price::MappingScroll),
+            Item::Solvent => self.get(Price::Solvent),
+            _ => 0,
+        }
+    }
+
+    /// Return the most a vendor on the top level will pay for a treasure
+    pub fn treasure_max_value(&self, treasure_type: TreasureType) -> u32 {
+        Treasure::rank(treasure_type) * self.get(Price::TreasureValue)
+    }
+
+    /// Scale a vendor's price for the level it's on
+    pub fn vendor_price_at(&self, cost: u32, z: u32) -> u32 {
+        cost + cost * self.vendor_depth * z / 100
+    }
+
+    /// Scale a treasure value for the level it's sold on
+    pub fn treasure_value_at(&self, value: u32, z: u32) -> u32 {
+        value + value * self.treasure_depth * z / 100
+    }
+}
+
+impl Default for Economy {
+    fn default() -> Economy {
+        Economy::classic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ruleset::Ruleset;
+
+    #[test]
+    fn classic_data_matches_original_prices() {
+        let economy = Economy::classic();
+
+        let armor = [
+            (ArmorType::Leather, 10, 1250),
+            (ArmorType::Chainmail, 20, 1500),
+            (ArmorType::Plate, 30, 2000),
+        ];
+
+        for (a, start, vendor) in armor {
+            assert_eq!(economy.armor_cost(a, false), start, "{:?}", a);
+            assert_eq!(economy.armor_cost(a, true), vendor, "{:?}", a);
+        }
+
+        let weapons = [
+            (WeaponType::Dagger, 10, 1250),
+            (WeaponType::Mace, 20, 1500),
+            (WeaponType::Sword, 30, 2000),
+        ];
+
+        for (w, start, vendor) in weapons {
+            assert_eq!(economy.weapon_cost(w, false), start, "{:?}", w);
+            assert_eq!(economy.weapon_cost(w, true), vendor, "{:?}", w);
+        }
+
+        assert_eq!(economy.get(Price::StartLamp), 20);
+        assert_eq!(economy.get(Price::Flare), 1);
+        assert_eq!(economy.get(Price::VendorLamp), 1000);
+        assert_eq!(economy.get(Price::Potion), 1000);
+        assert_eq!(economy.get(Price::Repair), 50);
+        assert_eq!(economy.get(Price::Cure), 500);
+
+        // Vendors paid up to 1500 GP per rank of treasure
+        assert_eq!(economy.treasure_max_value(TreasureType::RubyRed), 1500);
+        assert_eq!(economy.treasure_max_value(TreasureType::Silmaril), 12000);
+
+        // Prices didn't change with depth
+        assert_eq!(economy.vendor_price_at(1000, 7), 1000);
+        assert_eq!(economy.treasure_value_at(1000, 7), 1000);
+    }
+
+    #[test]
+    fn parse_keeps_classic_prices_left_out() {
+        let text = "[economy]\npotion = 400\nvendor_depth = 10\n";
+        let economy = Economy::parse(text).unwrap();
+
+        assert_eq!(economy.get(Price::Potion), 400);
+        assert_eq!(economy.get(Price::VendorLamp), 1000);
+        assert_eq!(economy.vendor_price_at(1000, 7), 1700);
+        assert_eq!(economy.treasure_value_at(1000, 7), 1000);
+    }
+
+    #[test]
+    fn rules_and_prices_share_a_file() {
+        let text = "[rules]\npreset = classic\n\n[economy]\npotion = 400\n";
+
+        let economy = Economy::parse(text).unwrap();
+        assert_eq!(economy.get(Price::Potion), 400);
+        assert_eq!(
+            economy.get(Price::Flare),
+            Economy::classic().get(Price::Flare)
+        );
+
+        assert_eq!(Ruleset::parse(text), Ok(Ruleset::classic()));
+    }
+
+    #[test]
+    fn parse_rejects_bad_settings() {
+        let bad = [
+            ("[economy]\npotion = cheap\n", 2),         // Not a number
+            ("[economy]\n\nunicorn = 10\n", 3),         // Unknown key
+            ("[economy]\ntreasure_value = 0\n", 2),     // Treasures worth nothing
+            ("potion = 400\n", 1),                      // No section
+            ("[economy]\n[vendor]\npotion = 400\n", 3), // Wrong section
+        ];
+
+        for (text, line) in bad {
+            assert_eq!(
+                Economy::parse(text).err(),
+                Some(Error::BadData(line)),
+                "{}",
+                text
+            );
+        }
+    }
+}
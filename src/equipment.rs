@@ -0,0 +1,25 @@
+use crate::armor::ArmorType;
+use crate::ring::RingType;
+use crate::shield::ShieldType;
+use crate::weapon::WeaponType;
+
+/// Gear beyond plain weapons and armor, found in chests or sold by vendors
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Equipment {
+    Shield(ShieldType),
+    Ring(RingType),
+    EnchantedWeapon(WeaponType, u32),
+    ElvenArmor(ArmorType),
+}
+
+/// What vendors have for sale
+pub const VENDOR_GOODS: [Equipment; 8] = [
+    Equipment::Shield(ShieldType::Buckler),
+    Equipment::Shield(ShieldType::Kite),
+    Equipment::Shield(ShieldType::Tower),
+    Equipment::Ring(RingType::Protection),
+    Equipment::Ring(RingType::Accuracy),
+    Equipment::Ring(RingType::Regeneration),
+    Equipment::EnchantedWeapon(WeaponType::Sword, 1),
+    Equipment::ElvenArmor(ArmorType::Chainmail),
+];
@@ -7,7 +7,20 @@ pub enum Error {
     Blind,
     OutOfBounds,
     BribeNotPossible,
-    BribeMustProposition, // Need to proposition successfully before calling bribe()
-    VendorNoTreasure,     // If the player is trying to get the vendor to offer on no treasure
+    VendorNoTreasure, // If the player is trying to get the vendor to offer on no treasure
     VendorMustOfferTreasure, // Need to offer a treasure before calling accept or reject
+    NoPath,           // No known way to get to a location
+    DataFile,         // Data file couldn't be read or is missing required entries
+    BadData(u32),     // Data file has a bad line
+    SpellNotAvailable, // Player doesn't know the spell or can't cast it now
+    InventoryFull,    // No room to carry another kind of item
+    NotCarrying,      // Player doesn't have the item
+    NotHere,          // The item isn't in the room
+    TooSoon,          // Needs more turns to pass before it can be used again
+    Cursed,           // A curse on the player won't allow it
+    OutOfStock,       // The vendor doesn't have any more of that
+    VendorOutOfGold,  // The vendor can't afford to pay that much
+    NotNeeded,        // Using the item wouldn't do anything
+    NotAtEntrance,    // The stash can only be reached from the entrance
+    BadAmount,        // An amount of gold that makes no sense
 }
@@ -1,20 +1,78 @@
 extern crate rand;
 
 use crate::armor::{Armor, ArmorType};
-use crate::curse::CurseType;
+use crate::config::GameConfig;
+use crate::curse::{CureType, Curse, CurseType};
+use crate::difficulty::Difficulty;
 use crate::dungeon::Dungeon;
+use crate::economy::Price;
+use crate::equipment::Equipment;
 use crate::error::Error;
-use crate::monster::{Monster, MonsterType};
+use crate::inventory::{Inventory, Item, Stack};
+use crate::monster::{Monster, MonsterDef, MonsterFlag, MonsterType, SpawnMode, MAX_REPUTATION};
+use crate::path::{self, PathStep};
 use crate::player::{Gender, Player, Race, Stat};
+use crate::ring::RingType;
 use crate::room::{Room, RoomType};
+use crate::shield::{Shield, ShieldType};
+use crate::spell::{Spell, SpellType, SPELLS};
+use crate::stats::Stats;
+use crate::stock::{Stock, Ware};
 use crate::treasure::{Treasure, TreasureType};
+use crate::view::{Memory, MonsterSighting, PlayerView, RememberedRoom};
 use crate::weapon::{Weapon, WeaponType};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
 use self::rand::rng;
 use self::rand::Rng;
 
+/// 1 in this many chance of a wandering monster moving each turn
+const WANDER_CHANCE: u32 = 8;
+
+/// Wandering monsters this close to the player tend to follow them
+const FOLLOW_DISTANCE: i32 = 3;
+
+/// Wounded monsters heal a hitpoint this often when regeneration is on
+const REGEN_TURNS: u32 = 5;
+
+/// A ring of regeneration restores a point of strength this often
+const RING_REGEN_TURNS: u32 = 10;
+
+/// The Palantir can be used once in this many turns, long enough that it
+/// can't map out the castle faster than walking it
+const PALANTIR_TURNS: u32 = 40;
+
+/// 1 in this many chance of the Palantir showing the Orb of Zot, which it
+/// only does when the Orb is on the player's level so it can't hand over the
+/// whole hunt
+const PALANTIR_ORB_CHANCE: u32 = 4;
+
+/// 1 in this many chance per turn, per stack, of Clumsiness draining dexterity
+const CLUMSY_CHANCE: u32 = 20;
+
+/// Vendors at or below this reputation attack on sight
+const HOSTILE_REPUTATION: i32 = -50;
+
+/// Reputation gained with a vendor for each sale
+const TRADE_REPUTATION: i32 = 2;
+
+/// Reputation lost with a vendor who gets fed up with haggling
+const WALK_AWAY_REPUTATION: i32 = 10;
+
+/// Reputation lost with other vendors on hearing the player attacked a vendor
+const ATTACK_NEWS: i32 = 60;
+
+/// Reputation lost with other vendors on hearing the player killed a vendor
+const MURDER_NEWS: i32 = 40;
+
+/// Turns for news to reach vendors on the same level
+const NEWS_LEVEL_TURNS: u32 = 5;
+
+/// Turns for news to reach vendors on other levels, where it arrives at half
+/// strength
+const NEWS_CASTLE_TURNS: u32 = 20;
+
 #[derive(Debug, Clone)]
 pub enum Event {
     None,
@@ -37,6 +95,33 @@ pub enum CombatEvent {
     MonsterWebbed,
     MonsterMiss,
     MonsterHit(u32, bool, bool, bool),
+    /// Hit, but the monster shrugged off half the damage
+    HitResisted(HitResult),
+    /// Hitpoints the monster healed
+    MonsterRegenerates(u32),
+    /// Player lost intelligence, true if it killed them
+    MonsterFear(bool),
+    /// Another monster came to an empty room nearby
+    MonsterCallsPack,
+    /// Number of attacks coming this round
+    MonsterMultiAttack(u32),
+    /// Damage and defeated, with armor no help
+    MonsterFire(u32, bool),
+    /// Strength the player gained from a spell
+    Healed(u32),
+    /// The player's shield stopped the blow
+    MonsterBlocked,
+}
+
+/// A monster's turn that's still being played out
+///
+/// Abilities can make a monster's turn more than a single attack, so
+/// `be_attacked()` reports them one event at a time.
+#[derive(Debug)]
+struct MonsterRound {
+    events: VecDeque<CombatEvent>, // Ability events still to report
+    attacks_left: u32,
+    web_broke: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -62,21 +147,59 @@ pub enum DrinkEvent {
     ChangeGender,
 }
 
+/// Word of something the player did to a vendor, making its way around the
+/// castle
+#[derive(Debug)]
+struct News {
+    x: u32,
+    y: u32,
+    z: u32,
+    change: i32, // Reputation change for vendors that hear it
+    age: u32,    // Turns since it happened
+}
+
+/// Something offered to a monster to leave the player alone
+#[derive(Debug, Clone, Copy)]
+pub enum Bribe {
+    Treasure(TreasureType),
+    Gold(u32),
+}
+
+/// How a vendor answers a counter-offer for a treasure
+#[derive(Debug, Clone, Copy)]
+pub enum HaggleEvent {
+    Accepted(u32),  // Sold for this many GP
+    Countered(u32), // Vendor's new offer
+    Refused,        // Vendor won't go any higher
+    WalkedAway,     // Vendor has had enough and won't trade any more
+}
+
 #[derive(Debug, Clone)]
 pub enum OrbEvent {
     BloodyHeap,
     Polymorph(MonsterType),
     GazeBack(MonsterType),
-    Item(RoomType, u32, u32, u32),
+    Item(RememberedRoom, u32, u32, u32),
     OrbOfZot(u32, u32, u32),
     SoapOpera,
 }
 
+#[derive(Debug, Clone)]
+pub enum SpellEvent {
+    Healed(u32),                             // Strength gained
+    Light(Vec<(Direction, RememberedRoom)>), // What's in each room next to the player
+    DetectMonsters(u32),                     // Monsters found on the level
+    Blink,
+    RemoveCurse(u32), // Curses lifted
+    Died,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum ChestEvent {
     Explode,
     Gas,
     Treasure(u32),
+    Equipment(Equipment),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -89,6 +212,15 @@ pub enum BookEvent {
     Sticky,
 }
 
+/// What happened when the player used up an item
+#[derive(Debug, Clone, Copy)]
+pub enum UseEvent {
+    Healed(u32), // Strength gained
+    Sight,       // Blindness cured
+    Mapped(u32), // Level revealed
+    Unstuck,     // Book freed from the player's hands
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum AttackType {
     Melee,
@@ -110,6 +242,38 @@ pub enum Stairs {
     Down,
 }
 
+/// Random events rolled ahead of time, so the Norn Stone can foretell them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Fate {
+    Chest,
+    Pool,
+    Gaze,
+}
+
+impl Fate {
+    /// Return the number of sides on the die rolled for this event
+    fn sides(&self) -> u32 {
+        match self {
+            Fate::Chest => 4,
+            Fate::Pool => 8,
+            Fate::Gaze => 6,
+        }
+    }
+}
+
+/// What the Norn Stone foretells about the room the player is in
+#[derive(Debug, Clone, Copy)]
+pub enum Omen {
+    ChestExplodes,
+    ChestGas,
+    ChestGold,
+    ChestEquipment,
+    Drink(DrinkEvent),
+    GazeHarms,
+    GazeVision,
+    GazeNothing,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RandomMessage {
     None,
@@ -142,80 +306,143 @@ pub enum GameState {
     Dead,
     Exit,
     Quit,
+    OutOfTime, // The turn limit ran out before the player got out
 }
 
 pub struct Game {
+    config: GameConfig,
+
     dungeon: Dungeon,
     player: Player,
 
+    memory: Memory,
+
     state: GameState,
 
     prev_dir: Direction,
 
-    currently_fighting: Option<Monster>,
+    monster_round: Option<MonsterRound>,
     bribe_possible: bool,
-    bribe_treasure: Option<TreasureType>,
     retreating: bool,
 
     spell_possible: bool,
 
-    vendors_angry: bool,
+    news: Vec<News>,
     vendor_treasure_price: Option<HashMap<TreasureType, u32>>,
+    vendor_mood: u32,   // 1 (surly) to 10 (generous)
+    vendor_pushes: u32, // Counter-offers made to this vendor
+    vendor_loot: Vec<(Ware, u32)>,
+
+    found_equipment: Option<Equipment>,
+
+    stash_gp: u32,    // Gold banked at the entrance
+    stash: Inventory, // Treasures banked at the entrance
+
+    fates: HashMap<Fate, u32>,
+    palantir_turn: Option<u32>,
 
     turn: u32,
     last_recipe_turn: u32,
+    out_of_time: bool, // Once the turn limit runs out, nothing else counts
+
+    monsters_slain: u32,
 
     lethargic: bool,
+    lifted_curses: Vec<CurseType>,
 
     player_moved_since_bribe: bool,
 }
 
 impl Game {
     pub fn new(xsize: u32, ysize: u32, zsize: u32) -> Game {
-        let dungeon = Dungeon::new(xsize, ysize, zsize);
+        Game::new_with_config(xsize, ysize, zsize, GameConfig::default())
+    }
+
+    /// Create a new game with non-default options
+    pub fn new_with_config(xsize: u32, ysize: u32, zsize: u32, config: GameConfig) -> Game {
+        let dungeon = Dungeon::new(xsize, ysize, zsize, &config);
 
         let mut player = Player::new();
         player.set_position(dungeon.entrance_x(), 0, 0);
 
+        if !config.rules.pack_limit {
+            *player.inventory_mut() = Inventory::unlimited();
+        }
+
+        // The player starts out knowing whatever the dungeon starts out
+        // discovered, namely the entrance
+        let mut memory = Memory::new(xsize, ysize, zsize);
+
+        for z in 0..zsize {
+            for y in 0..ysize {
+                for x in 0..xsize {
+                    let room = dungeon.room_at(x, y, z);
+
+                    if room.discovered {
+                        memory.remember(x, y, z, room.room_type());
+                    }
+                }
+            }
+        }
+
+        let difficulty = config.difficulty;
+
         Game {
+            config,
             dungeon,
             player,
+            memory,
             state: GameState::Init,
             prev_dir: Direction::South,
-            currently_fighting: None,
+            monster_round: None,
             bribe_possible: true,
-            bribe_treasure: None,
             retreating: false,
             spell_possible: false,
-            vendors_angry: false,
+            news: Vec::new(),
             vendor_treasure_price: None,
+            vendor_mood: 1,
+            vendor_pushes: 0,
+            vendor_loot: Vec::new(),
+
+            found_equipment: None,
+
+            stash_gp: 0,
+            stash: Inventory::unlimited(),
+
+            fates: [Fate::Chest, Fate::Pool, Fate::Gaze]
+                .into_iter()
+                .map(|f| (f, Game::fate_die(f, difficulty)))
+                .collect(),
+            palantir_turn: None,
             turn: 0,
             last_recipe_turn: 0,
+            monsters_slain: 0,
             lethargic: false,
+            out_of_time: false,
+            lifted_curses: Vec::new(),
             player_moved_since_bribe: true,
         }
     }
 
     /// Get a random monster type
-    fn rand_monster_type() -> MonsterType {
-        let monster_list = [
-            MonsterType::Kobold,
-            MonsterType::Orc,
-            MonsterType::Wolf,
-            MonsterType::Goblin,
-            MonsterType::Ogre,
-            MonsterType::Troll,
-            MonsterType::Bear,
-            MonsterType::Minotaur,
-            MonsterType::Gargoyle,
-            MonsterType::Chimera,
-            MonsterType::Balrog,
-            MonsterType::Dragon,
-        ];
+    fn rand_monster_type(&self) -> MonsterType {
+        let monster_list = self.config.monsters.spawnable();
 
         let mut rng = rng();
 
-        monster_list[rng.random_range(0..monster_list.len())]
+        monster_list[rng.random_range(0..monster_list.len())].monster_type
+    }
+
+    /// Return the location of the room next to the player in a direction
+    fn adjacent(&self, dir: Direction) -> (u32, u32) {
+        let (x, y) = (*self.player.x() as i32, *self.player.y() as i32);
+
+        match dir {
+            Direction::North => (x as u32, self.wrap_y(y - 1)),
+            Direction::South => (x as u32, self.wrap_y(y + 1)),
+            Direction::West => (self.wrap_x(x - 1), y as u32),
+            Direction::East => (self.wrap_x(x + 1), y as u32),
+        }
     }
 
     /// Wrap an x coordinate
@@ -271,27 +498,85 @@ impl Game {
         let z = rng.random_range(0..*self.dungeon.zsize());
 
         self.dungeon.room_at_mut(x, y, z).set_discovered(false);
+        self.memory.forget(x, y, z);
     }
 
     /// Mark the player's current room as empty
     fn make_current_room_empty(&mut self) {
-        let room = self
-            .dungeon
-            .room_at_mut(*self.player.x(), *self.player.y(), *self.player.z());
+        let (x, y, z) = (*self.player.x(), *self.player.y(), *self.player.z());
+
+        self.dungeon.room_at_mut(x, y, z).make_empty();
+
+        // The player is standing right there, so they see it happen
+        self.see_room(x, y, z);
+    }
+
+    /// Mark the player's current room as empty because they dealt with it
+    ///
+    /// Unlike picking up gold or flares, slaying a monster or opening a chest
+    /// or book counts toward the rooms the player cleared.
+    fn clear_current_room(&mut self) {
+        let (x, y, z) = (*self.player.x(), *self.player.y(), *self.player.z());
+
+        self.dungeon.room_at_mut(x, y, z).clear();
+
+        self.see_room(x, y, z);
+    }
+
+    /// Discover a room and remember what's in it
+    fn see_room(&mut self, x: u32, y: u32, z: u32) {
+        self.dungeon.discover(x, y, z);
+
+        // Blind players can't make out what's there to remember it
+        if self.player.is_blind() {
+            return;
+        }
+
+        self.memory
+            .remember(x, y, z, self.dungeon.room_at(x, y, z).room_type());
+    }
+
+    /// Return the castle as the player knows it
+    pub fn player_view(&self) -> PlayerView<'_> {
+        PlayerView::new(
+            &self.memory,
+            (
+                self.dungeon_xsize(),
+                self.dungeon_ysize(),
+                self.dungeon_zsize(),
+            ),
+            (self.player_x(), self.player_y(), self.player_z()),
+            RememberedRoom::from(self.room_at_player().room_type()),
+            self.monster_sighting(),
+            self.player.is_blind(),
+        )
+    }
+
+    /// Return how the monster in the player's room looks, if the player can
+    /// see it
+    fn monster_sighting(&self) -> Option<MonsterSighting> {
+        if self.player.is_blind() {
+            return None;
+        }
 
-        room.make_empty();
+        match self.room_at_player().room_type() {
+            RoomType::Monster(m) => Some(MonsterSighting {
+                wound_level: m.wound_level(),
+                webbed: *m.webbed() > 0,
+            }),
+            _ => None,
+        }
     }
 
     /// Return the room at the player position
-    pub fn room_at_player(&self) -> &Room {
+    pub(crate) fn room_at_player(&self) -> &Room {
         self.dungeon
             .room_at(*self.player.x(), *self.player.y(), *self.player.z())
     }
 
     /// Discover the room at the player position
     pub fn discover_room_at_player(&mut self) {
-        self.dungeon
-            .discover(*self.player.x(), *self.player.y(), *self.player.z())
+        self.see_room(*self.player.x(), *self.player.y(), *self.player.z())
     }
 
     /// Handle Gold room effects
@@ -309,7 +594,7 @@ impl Game {
     fn room_effect_flares(&mut self) -> Event {
         let flare_amount = Game::d(1, 5);
 
-        self.player.change_flares(flare_amount as i32);
+        self.give_item(Item::Flare, flare_amount);
 
         self.make_current_room_empty();
 
@@ -351,7 +636,7 @@ impl Game {
     fn room_effect_treasure(&mut self, treasure: Treasure) -> Event {
         self.make_current_room_empty();
 
-        self.player.treasure_add(treasure.treasure_type);
+        self.give_item(Item::Treasure(treasure.treasure_type), 1);
 
         Event::Treasure(treasure)
     }
@@ -364,20 +649,19 @@ impl Game {
             return Event::None;
         }
 
-        // If Vendors are not angry, head into vendor trade state instead of combat
-        if monster.monster_type() == MonsterType::Vendor && !self.vendors_angry {
+        // If the vendor isn't hostile, head into vendor trade state instead of combat
+        if monster.is_vendor() && monster.reputation() > HOSTILE_REPUTATION {
             self.state = GameState::Vendor;
             return Event::Vendor;
         }
 
-        self.currently_fighting = Some(monster.clone());
-
         // Monster gets first attack if player blind or lethargic
         if self.lethargic || self.player.is_blind() {
             self.state = GameState::MonsterAttack;
         } else {
             self.state = GameState::PlayerAttack;
-            self.bribe_possible = true;
+            self.bribe_possible =
+                !(self.config.rules.abilities && monster.has_flag(MonsterFlag::NoBribe));
             self.spell_possible = true;
         }
 
@@ -393,7 +677,59 @@ impl Game {
 
     /// True if the player can cast a spell
     pub fn spell_possible(&self) -> bool {
-        self.spell_possible && *self.player.stat(&Stat::Intelligence) > 14
+        self.spell_possible && !self.available_spells(true).is_empty()
+    }
+
+    /// Return the spells the player is smart enough to cast, either in combat
+    /// or while exploring
+    pub fn available_spells(&self, combat: bool) -> Vec<SpellType> {
+        let iq = *self.player.stat(&Stat::Intelligence);
+
+        SPELLS
+            .iter()
+            .filter(|s| iq >= s.min_iq && if combat { s.combat } else { s.explore })
+            .map(|s| s.spell_type)
+            .collect()
+    }
+
+    /// Roll whether a melee hit wears down the player's weapon
+    fn hit_wears_weapon(&self) -> bool {
+        self.config.rules.weapon_wear && Game::d(1, self.config.wear_chance) == 1
+    }
+
+    /// Take the cost of a spell out of the player's stats
+    ///
+    /// Returns true if it killed them.
+    fn pay_for_spell(&mut self, spell_type: SpellType) -> bool {
+        let spell = Spell::get(spell_type);
+
+        let st_cost = -(spell.st_cost as i32);
+        let iq_cost = -(spell.iq_cost as i32);
+
+        if st_cost < 0 && self.player.change_stat(Stat::Strength, st_cost) == 0 {
+            return true;
+        }
+
+        iq_cost < 0 && self.player.change_stat(Stat::Intelligence, iq_cost) == 0
+    }
+
+    /// Cast a spell in combat
+    pub fn cast_in_combat(&mut self, spell_type: SpellType) -> Result<CombatEvent, Error> {
+        if self.state != GameState::PlayerAttack {
+            return Err(Error::WrongState);
+        }
+
+        if !self.spell_possible() || !self.available_spells(true).contains(&spell_type) {
+            return Err(Error::SpellNotAvailable);
+        }
+
+        match spell_type {
+            SpellType::Web => self.spell_web(),
+            SpellType::Fireball => self.spell_fireball(),
+            SpellType::Deathspell => self.spell_deathspell(),
+            SpellType::Heal => self.spell_heal(),
+            _ => Err(Error::SpellNotAvailable),
+        }
     }
 
     /// Handle player attacking monster
@@ -422,18 +758,19 @@ impl Game {
                     return Ok(CombatEvent::BookHands);
                 }
 
-                hit = *self.player.stat(&Stat::Dexterity)
-                    >= (Game::d(1, 20) + (self.player.is_blind() as u32) * 3);
+                let to_hit = *self.player.stat(&Stat::Dexterity) as i32
+                    + self.player.weapon().bonus() as i32
+                    + self.player.ring().hit_bonus() as i32
+                    + self.config.difficulty.hit_bonus();
+
+                hit = to_hit >= (Game::d(1, 20) + (self.player.is_blind() as u32) * 3) as i32;
 
                 if hit {
                     damage = self.player.weapon().damage();
                 }
             }
             AttackType::Fireball => {
-                // -1 ST and IQ
-                if self.player.change_stat(Stat::Strength, -1) == 0
-                    || self.player.change_stat(Stat::Intelligence, -1) == 0
-                {
+                if self.pay_for_spell(SpellType::Fireball) {
                     self.state = GameState::Dead;
                     return Ok(CombatEvent::Died);
                 }
@@ -464,14 +801,32 @@ impl Game {
             };
 
             let mut next_state = GameState::MonsterAttack;
+            let mut resisted = false;
+            let mut vendor_stock = None;
+
+            let wears = attack_type == AttackType::Melee && self.hit_wears_weapon();
+
+            if let Some(monster) =
+                self.dungeon
+                    .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+            {
+                if attack_type == AttackType::Melee
+                    && self.config.rules.abilities
+                    && monster.has_flag(MonsterFlag::Resist)
+                {
+                    resisted = true;
+                    result.damage = result.damage.div_ceil(2);
+                }
 
-            if let Some(ref mut monster) = self.currently_fighting {
                 if attack_type == AttackType::Melee
-                    && monster.can_break_weapon()
-                    && Game::d(1, 8) == 1
+                    && !self.player.weapon().is_enchanted()
+                    && monster.break_chance() > 0
+                    && Game::d(1, monster.break_chance()) == 1
                 {
                     result.broke_weapon = true;
                     self.player.set_weapon(Weapon::new(WeaponType::None));
+                } else if wears {
+                    result.broke_weapon = self.player.wear_weapon(1);
                 }
 
                 result.defeated = monster.take_damage(result.damage);
@@ -480,27 +835,12 @@ impl Game {
                     next_state = GameState::Move;
 
                     // Take vendor's wares
-                    if monster.monster_type() == MonsterType::Vendor {
+                    if monster.is_vendor() {
                         result.killed_vendor = true;
-
-                        self.player
-                            .change_stat(Stat::Strength, Game::d(1, 6) as i32);
-                        self.player
-                            .change_stat(Stat::Intelligence, Game::d(1, 6) as i32);
-                        self.player
-                            .change_stat(Stat::Dexterity, Game::d(1, 6) as i32);
-
-                        self.player.set_armor_by_type(ArmorType::Plate);
-                        self.player.set_weapon_by_type(WeaponType::Sword);
-
-                        if !self.player.has_lamp() {
-                            self.player.set_lamp(true);
-                            result.got_lamp = true;
-                        }
+                        vendor_stock = monster.take_stock();
                     } else {
                         // Non-vendor creature
                         if monster.has_runestaff() {
-                            self.player.give_runestaff(true);
                             result.got_runestaff = true;
                         }
 
@@ -512,13 +852,31 @@ impl Game {
             }
 
             if result.defeated {
-                self.make_current_room_empty();
-                self.currently_fighting = None;
+                self.clear_current_room();
+                self.monsters_slain += 1;
+            }
+
+            if result.killed_vendor {
+                self.spread_word(-MURDER_NEWS);
+            }
+
+            if let Some(stock) = vendor_stock {
+                result.treasure = stock.purse();
+                result.got_lamp = self.loot_vendor(&stock);
+            }
+
+            if result.got_runestaff {
+                self.give_item(Item::Runestaff, 1);
             }
 
             self.player.add_gp(result.treasure as i32);
 
             self.state = next_state;
+
+            if resisted {
+                return Ok(CombatEvent::HitResisted(result));
+            }
+
             return Ok(CombatEvent::Hit(result));
         }
 
@@ -535,7 +893,92 @@ impl Game {
         }
     }
 
+    /// Use the current monster's abilities at the start of its turn
+    ///
+    /// Return the events to report and the number of attacks to make.
+    fn monster_abilities(&mut self) -> (VecDeque<CombatEvent>, u32) {
+        let mut events = VecDeque::new();
+
+        let monster = self
+            .dungeon
+            .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+            .expect("being attacked, but not by any monster");
+
+        let monster_type = monster.monster_type();
+        let attacks = monster.attacks();
+
+        if monster.has_flag(MonsterFlag::Regenerate) {
+            let healed = monster.heal(Game::d(1, 2));
+
+            if healed > 0 {
+                events.push_back(CombatEvent::MonsterRegenerates(healed));
+            }
+        }
+
+        let fear = monster.has_flag(MonsterFlag::Fear) && Game::d(1, 4) == 1;
+        let pack = monster.has_flag(MonsterFlag::Pack) && Game::d(1, 5) == 1;
+
+        if fear {
+            let died = self.player.change_stat(Stat::Intelligence, -1) == 0;
+
+            events.push_back(CombatEvent::MonsterFear(died));
+
+            if died {
+                return (events, 0);
+            }
+        }
+
+        if pack && self.call_pack(monster_type) {
+            events.push_back(CombatEvent::MonsterCallsPack);
+        }
+
+        if attacks > 1 {
+            events.push_back(CombatEvent::MonsterMultiAttack(attacks));
+        }
+
+        (events, attacks)
+    }
+
+    /// Bring a new monster into an empty room next to the player
+    ///
+    /// Return true if there was room for it.
+    fn call_pack(&mut self, monster_type: MonsterType) -> bool {
+        let x = *self.player.x() as i32;
+        let y = *self.player.y() as i32;
+        let z = *self.player.z();
+
+        let empty: Vec<(u32, u32)> = [(x, y - 1), (x, y + 1), (x - 1, y), (x + 1, y)]
+            .iter()
+            .map(|&(nx, ny)| (self.wrap_x(nx), self.wrap_y(ny)))
+            .filter(|&(nx, ny)| *self.dungeon.room_at(nx, ny, z).room_type() == RoomType::Empty)
+            .collect();
+
+        if empty.is_empty() {
+            return false;
+        }
+
+        let (nx, ny) = empty[rng().random_range(0..empty.len())];
+
+        let def = self.monster_def(monster_type);
+
+        // Pack members are as tough as anything else on the level
+        let mut monster = match self.config.spawn_mode {
+            SpawnMode::Classic => Monster::new(def, false),
+            SpawnMode::DepthScaled => Monster::new_at_depth(def, false, z, *self.dungeon.zsize()),
+        };
+
+        monster.scale(self.config.difficulty.monster_percent());
+
+        self.dungeon.room_at_mut(nx, ny, z).roomtype = RoomType::Monster(monster);
+
+        true
+    }
+
     /// Handle a monster attack
+    ///
+    /// If the monster has abilities, this reports them before the attack, and
+    /// the state stays MonsterAttack until all of the monster's attacks for
+    /// the round are done.
     pub fn be_attacked(&mut self) -> Result<CombatEvent, Error> {
         if self.state != GameState::MonsterAttack {
             return Err(Error::WrongState);
@@ -544,67 +987,113 @@ impl Game {
         self.bribe_possible = false;
         self.spell_possible = false;
 
-        let mut web_broke = false;
-
-        // Check for web breaking / stuck
-        if let Some(ref mut monster) = self.currently_fighting {
-            if *monster.webbed() > 0 {
-                if monster.weaken_web() {
-                    web_broke = true;
-                } else {
-                    self.state = GameState::PlayerAttack;
-                    return Ok(CombatEvent::MonsterWebbed);
+        if self.monster_round.is_none() {
+            let mut web_broke = false;
+
+            // Check for web breaking / stuck
+            if let Some(monster) =
+                self.dungeon
+                    .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+            {
+                if *monster.webbed() > 0 {
+                    if monster.weaken_web() {
+                        web_broke = true;
+                    } else {
+                        self.state = GameState::PlayerAttack;
+                        return Ok(CombatEvent::MonsterWebbed);
+                    }
                 }
+            } else {
+                panic!("being attacked, but not by any monster");
             }
-        } else {
-            panic!("being attacked, but not by any monster");
+
+            let (events, attacks_left) = if self.config.rules.abilities {
+                self.monster_abilities()
+            } else {
+                (VecDeque::new(), 1)
+            };
+
+            self.monster_round = Some(MonsterRound {
+                events,
+                attacks_left,
+                web_broke,
+            });
+        }
+
+        let round = self.monster_round.as_mut().unwrap();
+
+        // Report abilities before attacking
+        if let Some(event) = round.events.pop_front() {
+            if let CombatEvent::MonsterFear(true) = event {
+                self.monster_round = None;
+                self.state = GameState::Dead;
+            }
+
+            return Ok(event);
+        }
+
+        let web_broke = std::mem::take(&mut round.web_broke);
+
+        round.attacks_left -= 1;
+        let round_over = round.attacks_left == 0;
+
+        if round_over {
+            self.monster_round = None;
         }
 
-        let hit = *self.player.stat(&Stat::Dexterity)
-            < (Game::d(3, 7) + (self.player.is_blind() as u32) * 3);
+        let dodge = *self.player.stat(&Stat::Dexterity) as i32 + self.config.difficulty.hit_bonus();
 
-        let mut combat_event = None;
+        let hit = dodge < (Game::d(3, 7) + (self.player.is_blind() as u32) * 3) as i32;
+
+        let mut combat_event = CombatEvent::MonsterMiss;
         let mut defeated = false;
 
         // Handle player hit
         if hit {
-            if let Some(ref mut monster) = self.currently_fighting {
+            if let Some(monster) =
+                self.dungeon
+                    .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+            {
                 let damage = monster.damage();
-                let armor_value = self.player.armor().armor_value();
 
-                let st_damage = std::cmp::max(damage as isize - armor_value as isize, 0) as u32;
-                defeated = self.player.damage_st(st_damage);
+                let fire = self.config.rules.abilities
+                    && monster.has_flag(MonsterFlag::Fire)
+                    && Game::d(1, 3) == 1;
+
+                if fire {
+                    // Armor doesn't help against fire
+                    defeated = self.player.damage_st(damage);
 
-                let armor_damage = std::cmp::min(damage, armor_value);
-                let armor_destroyed = self.player.damage_armor(armor_damage);
+                    combat_event = CombatEvent::MonsterFire(damage, defeated);
+                } else if Game::d(1, 100) <= self.player.shield().block_chance() {
+                    combat_event = CombatEvent::MonsterBlocked;
+                } else {
+                    let armor_value = self.player.armor().armor_value();
+                    let protection = armor_value + self.player.ring().armor_value();
+
+                    let st_damage = std::cmp::max(damage as isize - protection as isize, 0) as u32;
+                    defeated = self.player.damage_st(st_damage);
 
-                combat_event = Some(CombatEvent::MonsterHit(
-                    st_damage,
-                    defeated,
-                    armor_destroyed,
-                    web_broke,
-                ));
+                    let armor_damage = std::cmp::min(damage, armor_value);
+                    let armor_destroyed = self.player.damage_armor(armor_damage);
+
+                    combat_event =
+                        CombatEvent::MonsterHit(st_damage, defeated, armor_destroyed, web_broke);
+                }
             } else {
                 panic!("being attacked, but not by any monster");
             }
         }
 
         // Set next state
-        if hit {
-            if defeated {
-                self.state = GameState::Dead;
-            } else {
-                self.state_after_monster_attack();
-            }
-
-            if let Some(c_event) = combat_event {
-                return Ok(c_event);
-            }
+        if defeated {
+            self.monster_round = None;
+            self.state = GameState::Dead;
+        } else if round_over {
+            self.state_after_monster_attack();
         }
 
-        self.state_after_monster_attack();
-
-        Ok(CombatEvent::MonsterMiss)
+        Ok(combat_event)
     }
 
     /// Handle retreat
@@ -622,8 +1111,13 @@ impl Game {
         Ok(())
     }
 
-    /// Handle bribe
-    pub fn bribe_accept(&mut self) -> Result<(), Error> {
+    /// Offer a monster a bribe
+    ///
+    /// The more the bribe is worth next to the monster's asking price, the
+    /// likelier it is to take it. A refused bribe costs the player the turn.
+    ///
+    /// Returns true if the monster took the bribe
+    pub fn bribe_offer(&mut self, bribe: Bribe) -> Result<bool, Error> {
         if self.state != GameState::PlayerAttack {
             return Err(Error::WrongState);
         }
@@ -632,55 +1126,62 @@ impl Game {
             return Err(Error::BribeNotPossible);
         }
 
-        if let Some(t_type) = self.bribe_treasure {
-            if self.player.remove_treasure(t_type) {
-                // Player had the treasure
-                self.state = GameState::Move;
+        let value = match bribe {
+            Bribe::Treasure(t) if self.player.has_treasure(t) => self.treasure_max_value(t),
+            Bribe::Treasure(_) => return Err(Error::NotCarrying),
+            Bribe::Gold(amount) if amount <= *self.player.gp() => amount,
+            Bribe::Gold(_) => return Err(Error::NotEnoughGP),
+        };
 
-                // Check if we're bribing a vendor
-                let roomtype = &self
-                    .dungeon
-                    .room_at(*self.player.x(), *self.player.y(), *self.player.z())
-                    .roomtype;
+        if Game::d(1, 100) > self.bribe_chance(value) {
+            self.state = GameState::MonsterAttack;
+            return Ok(false);
+        }
 
-                if let RoomType::Monster(m) = roomtype {
-                    if m.monster_type() == MonsterType::Vendor {
-                        // If we are, make them unangry
-                        self.vendors_angry = false;
-                    }
-                }
-            } else {
-                panic!("we really thought player had a treasure");
+        match bribe {
+            Bribe::Treasure(t) => {
+                self.player.remove_treasure(t);
             }
+            Bribe::Gold(amount) => self.player.spend(amount)?,
+        }
 
-            self.bribe_treasure = None;
-        } else {
-            // No current bribeable treasure
-            return Err(Error::BribeMustProposition);
+        self.state = GameState::Move;
+
+        // If we're bribing a vendor, it calms down, if not all the way
+        if let Some(m) =
+            self.dungeon
+                .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+        {
+            if m.is_vendor() {
+                m.set_reputation(HOSTILE_REPUTATION / 2);
+            }
         }
 
         self.player_moved_since_bribe = false;
 
-        Ok(())
+        Ok(true)
     }
 
-    /// Player declines bribe offer
-    pub fn bribe_decline(&mut self) -> Result<(), Error> {
-        if self.state != GameState::PlayerAttack {
-            return Err(Error::WrongState);
-        }
+    /// Return the percent chance the monster in the room takes a bribe
+    ///
+    /// Offering its asking price is an even bet, and no offer is ever sure to
+    /// be taken or refused.
+    fn bribe_chance(&self, value: u32) -> u32 {
+        (value * 50 / self.bribe_price().max(1)).clamp(5, 95)
+    }
 
-        if !self.bribe_possible() {
-            return Err(Error::BribeNotPossible);
+    /// Return what the monster in the room would gladly take to go away
+    ///
+    /// Tougher monsters want more, and wounded ones settle for less.
+    fn bribe_price(&self) -> u32 {
+        match self.room_at_player().room_type() {
+            RoomType::Monster(m) => (m.hp() + m.damage()) * self.config.economy.get(Price::Bribe),
+            _ => 0,
         }
-
-        self.state = GameState::MonsterAttack;
-
-        Ok(())
     }
 
-    /// Get the bribe treasure
-    pub fn bribe_proposition(&mut self) -> Result<Option<TreasureType>, Error> {
+    /// Player gives up on bribing, and the monster attacks
+    pub fn bribe_decline(&mut self) -> Result<(), Error> {
         if self.state != GameState::PlayerAttack {
             return Err(Error::WrongState);
         }
@@ -689,25 +1190,9 @@ impl Game {
             return Err(Error::BribeNotPossible);
         }
 
-        let treasures = self.player.get_treasures();
-
-        let count = treasures.len();
-
-        if count == 0 {
-            // If you try to bribe with no treasures, the monsters attack
-            self.state = GameState::MonsterAttack;
-            return Ok(None);
-        }
-
-        let mut rng = rng();
-
-        let i = rng.random_range(0..count);
-
-        let t_type = treasures[i];
-
-        self.bribe_treasure = Some(t_type);
+        self.state = GameState::MonsterAttack;
 
-        Ok(self.bribe_treasure)
+        Ok(())
     }
 
     /// After the monster's final attack
@@ -718,22 +1203,22 @@ impl Game {
     }
 
     /// Fireball spell
-    pub fn spell_fireball(&mut self) -> Result<CombatEvent, Error> {
+    fn spell_fireball(&mut self) -> Result<CombatEvent, Error> {
         self.attack_with(AttackType::Fireball)
     }
 
     /// Deathspell spell
-    pub fn spell_deathspell(&mut self) -> Result<CombatEvent, Error> {
+    fn spell_deathspell(&mut self) -> Result<CombatEvent, Error> {
         self.attack_with(AttackType::Deathspell)
     }
 
     /// Web spell
-    pub fn spell_web(&mut self) -> Result<CombatEvent, Error> {
+    fn spell_web(&mut self) -> Result<CombatEvent, Error> {
         if self.state != GameState::PlayerAttack {
             return Err(Error::WrongState);
         }
 
-        if self.player.change_stat(Stat::Strength, -1) == 0 {
+        if self.pay_for_spell(SpellType::Web) {
             self.state = GameState::Dead;
             return Ok(CombatEvent::Died);
         }
@@ -750,7 +1235,10 @@ impl Game {
             got_lamp: false,
         };
 
-        if let Some(ref mut monster) = self.currently_fighting {
+        if let Some(monster) =
+            self.dungeon
+                .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+        {
             monster.set_webbed(Game::d(1, 6) + 1);
         } else {
             panic!("not fighting a monster");
@@ -759,15 +1247,152 @@ impl Game {
         Ok(CombatEvent::Hit(result))
     }
 
-    /// Check for a room event
-    pub fn room_effect(&mut self) -> Event {
-        let roomtype;
-
-        {
-            let room = self
-                .dungeon
-                .room_at(*self.player.x(), *self.player.y(), *self.player.z());
-            roomtype = room.roomtype.clone();
+    /// Heal spell, in combat
+    fn spell_heal(&mut self) -> Result<CombatEvent, Error> {
+        if self.state != GameState::PlayerAttack {
+            return Err(Error::WrongState);
+        }
+
+        if self.pay_for_spell(SpellType::Heal) {
+            self.state = GameState::Dead;
+            return Ok(CombatEvent::Died);
+        }
+
+        self.state = GameState::MonsterAttack;
+
+        Ok(CombatEvent::Healed(self.heal_player()))
+    }
+
+    /// Trade intelligence for strength
+    ///
+    /// Returns the strength gained.
+    fn heal_player(&mut self) -> u32 {
+        let before = *self.player.stat(&Stat::Strength);
+
+        let after = self
+            .player
+            .change_stat(Stat::Strength, Game::d(1, 4) as i32 + 1);
+
+        after - before
+    }
+
+    /// Cast a spell outside of combat
+    pub fn cast(&mut self, spell_type: SpellType) -> Result<SpellEvent, Error> {
+        if self.state != GameState::Move && self.state != GameState::Init {
+            return Err(Error::WrongState);
+        }
+
+        if !self.available_spells(false).contains(&spell_type) {
+            return Err(Error::SpellNotAvailable);
+        }
+
+        if spell_type == SpellType::Light && self.player.is_blind() {
+            return Err(Error::Blind);
+        }
+
+        if self.pay_for_spell(spell_type) {
+            self.state = GameState::Dead;
+            return Ok(SpellEvent::Died);
+        }
+
+        let event = match spell_type {
+            SpellType::Heal => SpellEvent::Healed(self.heal_player()),
+            SpellType::Light => SpellEvent::Light(self.spell_light()),
+            SpellType::DetectMonsters => SpellEvent::DetectMonsters(self.spell_detect_monsters()),
+            SpellType::Blink => {
+                self.spell_blink();
+                SpellEvent::Blink
+            }
+            SpellType::RemoveCurse => SpellEvent::RemoveCurse(self.cure_curses(CureType::Spell)),
+            _ => return Err(Error::SpellNotAvailable),
+        };
+
+        Ok(event)
+    }
+
+    /// Light up the rooms next to the player
+    fn spell_light(&mut self) -> Vec<(Direction, RememberedRoom)> {
+        let z = *self.player.z();
+
+        [
+            Direction::North,
+            Direction::South,
+            Direction::West,
+            Direction::East,
+        ]
+        .iter()
+        .map(|&dir| {
+            let (x, y) = self.adjacent(dir);
+
+            self.see_room(x, y, z);
+
+            let room_type = RememberedRoom::from(self.dungeon.room_at(x, y, z).room_type());
+
+            (dir, room_type)
+        })
+        .collect()
+    }
+
+    /// Learn where the monsters are on the player's level
+    ///
+    /// Returns the number of monsters found, not counting vendors.
+    fn spell_detect_monsters(&mut self) -> u32 {
+        let z = *self.player.z();
+        let mut count = 0;
+
+        for y in 0..self.dungeon_ysize() {
+            for x in 0..self.dungeon_xsize() {
+                if let RoomType::Monster(m) = self.dungeon.room_at(x, y, z).room_type() {
+                    if !m.is_vendor() {
+                        count += 1;
+                    }
+
+                    self.memory
+                        .remember(x, y, z, self.dungeon.room_at(x, y, z).room_type());
+                }
+            }
+        }
+
+        count
+    }
+
+    /// Teleport to a random room up to two steps away
+    fn spell_blink(&mut self) {
+        let (px, py) = (*self.player.x(), *self.player.y());
+        let (xsize, ysize) = (self.dungeon_xsize(), self.dungeon_ysize());
+
+        let mut rooms = Vec::new();
+
+        for y in 0..ysize {
+            for x in 0..xsize {
+                let dx = Game::wrapped_offset(px, x, xsize).abs();
+                let dy = Game::wrapped_offset(py, y, ysize).abs();
+
+                if dx + dy > 0 && dx + dy <= 2 {
+                    rooms.push((x, y));
+                }
+            }
+        }
+
+        let (x, y) = rooms[rng().random_range(0..rooms.len())];
+
+        self.player.set_x(x);
+        self.player.set_y(y);
+
+        self.player_moved_since_bribe = true;
+
+        self.discover_room_at_player();
+    }
+
+    /// Check for a room event
+    pub fn room_effect(&mut self) -> Event {
+        let roomtype;
+
+        {
+            let room = self
+                .dungeon
+                .room_at(*self.player.x(), *self.player.y(), *self.player.z());
+            roomtype = room.roomtype.clone();
         }
 
         match roomtype {
@@ -796,6 +1421,8 @@ impl Game {
             return Err(Error::CantGo);
         }
 
+        self.leave_room();
+
         if x > 7 || y > 7 || z > 7 {
             return Err(Error::OutOfBounds);
         }
@@ -809,8 +1436,8 @@ impl Game {
 
             if let RoomType::Warp(true) = room.roomtype {
                 found_orb_of_zot = true;
-                p.give_runestaff(false);
-                p.give_orb_of_zot(true);
+                p.give_runestaff(false)?;
+                p.give_orb_of_zot(true)?;
             }
         }
 
@@ -846,15 +1473,24 @@ impl Game {
             }
         }
 
+        self.leave_room();
         self.discover_room_at_player();
 
         Ok(())
     }
 
+    /// Forget anything that was only on offer in the room the player is
+    /// leaving
+    fn leave_room(&mut self) {
+        self.found_equipment = None;
+    }
+
     /// Handle a move command
     pub fn move_dir(&mut self, dir: Direction) {
         self.prev_dir = dir;
 
+        self.leave_room();
+
         let roomtype = self.room_at_player().roomtype.clone();
 
         // Handle exit special case
@@ -889,6 +1525,73 @@ impl Game {
         self.discover_room_at_player();
     }
 
+    /// Find a path from the player to the nearest room matching `is_goal`
+    ///
+    /// The path only crosses rooms the player remembers, and steers clear of
+    /// remembered monsters, vendors, sinkholes, and warps.
+    fn path_to_nearest<G>(&self, is_goal: G) -> Result<Vec<PathStep>, Error>
+    where
+        G: Fn(u32, u32, u32) -> bool,
+    {
+        if self.player.is_blind() {
+            return Err(Error::Blind);
+        }
+
+        let known = |x, y, z| self.memory.recall(x, y, z);
+
+        let size = (
+            self.dungeon_xsize(),
+            self.dungeon_ysize(),
+            self.dungeon_zsize(),
+        );
+
+        let start = (self.player_x(), self.player_y(), self.player_z());
+
+        path::find_path(size, start, known, is_goal).ok_or(Error::NoPath)
+    }
+
+    /// Find a path from the player to a location
+    pub fn path_to(&self, x: u32, y: u32, z: u32) -> Result<Vec<PathStep>, Error> {
+        if x >= self.dungeon_xsize() || y >= self.dungeon_ysize() || z >= self.dungeon_zsize() {
+            return Err(Error::OutOfBounds);
+        }
+
+        self.path_to_nearest(|gx, gy, gz| (gx, gy, gz) == (x, y, z))
+    }
+
+    /// Find a path from the player to the nearest known stairs
+    pub fn path_to_stairs(&self) -> Result<Vec<PathStep>, Error> {
+        let here = (self.player_x(), self.player_y(), self.player_z());
+
+        self.path_to_nearest(|x, y, z| {
+            (x, y, z) != here
+                && matches!(
+                    self.memory.recall(x, y, z),
+                    Some(RememberedRoom::StairsUp) | Some(RememberedRoom::StairsDown)
+                )
+        })
+    }
+
+    /// Find a path from the player to the nearest undiscovered room
+    pub fn path_to_unexplored(&self) -> Result<Vec<PathStep>, Error> {
+        self.path_to_nearest(|x, y, z| self.memory.recall(x, y, z).is_none())
+    }
+
+    /// Take one step along a path
+    pub fn take_step(&mut self, step: PathStep) -> Result<(), Error> {
+        if self.state != GameState::Init && self.state != GameState::Move {
+            return Err(Error::WrongState);
+        }
+
+        match step {
+            PathStep::Move(dir) => {
+                self.move_dir(dir);
+                Ok(())
+            }
+            PathStep::Stairs(stairs) => self.move_stairs(stairs),
+        }
+    }
+
     /// Begin negotiations to sell a treasure to a vendor
     pub fn vendor_treasure_offer(&mut self) -> Result<HashMap<TreasureType, u32>, Error> {
         if self.state != GameState::Vendor {
@@ -901,43 +1604,168 @@ impl Game {
             return Err(Error::VendorNoTreasure);
         }
 
+        if self.curse_strength(CurseType::Greed) > 0 {
+            return Err(Error::Cursed);
+        }
+
+        let purse = self.vendor_purse();
+
+        // Friends get better offers
+        let reputation = self.vendor_reputation().unwrap_or(0);
+
         let mut hash = HashMap::new();
 
         for t in self.player_get_treasures() {
-            let max_value = Treasure::treasure_max_value(t);
+            let max_value = self.treasure_max_value(t);
 
-            let value = Game::d(1, max_value);
+            let value = Game::d(1, max_value) * (100 + reputation / 4) as u32 / 100;
+            let value = value.min(max_value).min(purse);
 
             hash.insert(t, value);
         }
 
         self.vendor_treasure_price = Some(hash.clone());
+        self.vendor_mood = Game::d(1, 10);
+        self.vendor_pushes = 0;
 
         Ok(hash)
     }
 
+    /// Ask a vendor for more than they offered for a treasure
+    ///
+    /// A good mood and a sharp mind help, but every push wears the vendor's
+    /// patience thinner, and greedy asks can make them walk away. Asking
+    /// nothing at all is an error.
+    pub fn vendor_treasure_counter(
+        &mut self,
+        treasure_type: TreasureType,
+        ask: u32,
+    ) -> Result<HaggleEvent, Error> {
+        if self.state != GameState::Vendor {
+            return Err(Error::WrongState);
+        }
+
+        if ask == 0 {
+            return Err(Error::BadAmount);
+        }
+
+        let offer = match self.vendor_treasure_price {
+            Some(ref hash) => hash.get(&treasure_type).copied().unwrap_or(0),
+            None => return Err(Error::VendorMustOfferTreasure),
+        };
+
+        if offer == 0 || !self.player.has_treasure(treasure_type) {
+            return Err(Error::VendorNoTreasure);
+        }
+
+        let purse = self.vendor_purse();
+
+        if offer > purse {
+            return Err(Error::VendorOutOfGold);
+        }
+
+        if ask <= offer {
+            self.set_vendor_price(treasure_type, ask);
+            self.vendor_treasure_accept(treasure_type)?;
+            return Ok(HaggleEvent::Accepted(ask));
+        }
+
+        self.vendor_pushes += 1;
+
+        // The offer came out of both of these, so neither is below it
+        let max_value = self.treasure_max_value(treasure_type).min(purse);
+
+        // No vendor pays more than a treasure is worth or than they have
+        if ask > max_value {
+            return Ok(HaggleEvent::Refused);
+        }
+
+        let iq = *self.player.stat(&Stat::Intelligence);
+        let greed = ((ask - offer) as u64 * 100 / max_value as u64) as u32;
+
+        let accept_chance =
+            (self.vendor_mood * 5 + iq * 2).saturating_sub(greed + self.vendor_pushes * 10);
+
+        let walk_chance =
+            (self.vendor_pushes * 10 + greed / 2).saturating_sub(self.vendor_mood * 3);
+
+        if Game::d(1, 100) <= accept_chance {
+            self.set_vendor_price(treasure_type, ask);
+            self.vendor_treasure_accept(treasure_type)?;
+            return Ok(HaggleEvent::Accepted(ask));
+        }
+
+        if Game::d(1, 100) <= walk_chance {
+            self.change_vendor_reputation(-WALK_AWAY_REPUTATION);
+            self.vendor_complete();
+            return Ok(HaggleEvent::WalkedAway);
+        }
+
+        // Meet the player part way, less so as the vendor sours
+        let counter = offer + (ask.min(max_value) - offer) * self.vendor_mood / 20;
+
+        self.vendor_mood = (self.vendor_mood - 1).max(1);
+
+        if counter <= offer {
+            return Ok(HaggleEvent::Refused);
+        }
+
+        self.set_vendor_price(treasure_type, counter);
+
+        Ok(HaggleEvent::Countered(counter))
+    }
+
+    /// Change what the vendor is offering for a treasure
+    fn set_vendor_price(&mut self, treasure_type: TreasureType, price: u32) {
+        if let Some(ref mut hash) = self.vendor_treasure_price {
+            hash.insert(treasure_type, price);
+        }
+    }
+
     /// Accept selling a treasure
     pub fn vendor_treasure_accept(&mut self, treasure_type: TreasureType) -> Result<(), Error> {
         if self.vendor_treasure_price.is_none() {
             return Err(Error::VendorMustOfferTreasure);
         }
 
-        if !self.player.remove_treasure(treasure_type) {
+        let value = match self.vendor_treasure_price {
+            Some(ref hash) => *hash.get(&treasure_type).ok_or(Error::VendorNoTreasure)?,
+            None => return Err(Error::VendorMustOfferTreasure),
+        };
+
+        if !self.player.has_treasure(treasure_type) {
             return Err(Error::VendorNoTreasure);
         }
 
+        if !self.vendor_stock_mut().is_some_and(|s| s.pay(value)) {
+            return Err(Error::VendorOutOfGold);
+        }
+
+        self.player.remove_treasure(treasure_type);
+        self.player.add_gp(value as i32);
+        self.set_vendor_price(treasure_type, 0);
+        self.change_vendor_reputation(TRADE_REPUTATION);
+
+        // The vendor can't offer more for the rest than is left in the purse
+        let purse = self.vendor_purse();
+
         if let Some(ref mut hash) = self.vendor_treasure_price {
-            if let Some(value) = hash.get(&treasure_type) {
-                self.player.add_gp(*value as i32);
-                hash.insert(treasure_type, 0);
-            } else {
-                return Err(Error::VendorNoTreasure);
+            for price in hash.values_mut() {
+                *price = (*price).min(purse);
             }
         }
 
         Ok(())
     }
 
+    /// Return what the vendor is offering for a treasure right now
+    pub fn vendor_treasure_price(&self, treasure_type: TreasureType) -> u32 {
+        self.vendor_treasure_price
+            .as_ref()
+            .and_then(|hash| hash.get(&treasure_type).copied())
+            .unwrap_or(0)
+    }
+
     /// Check if you can afford armor
     pub fn vendor_can_afford_armor(&self) -> bool {
         self.vendor_can_afford_armor_type(ArmorType::Leather)
@@ -945,7 +1773,7 @@ impl Game {
 
     /// Check if you can afford a specific armor
     pub fn vendor_can_afford_armor_type(&self, armor_type: ArmorType) -> bool {
-        self.player_gp() >= Armor::cost(armor_type, true)
+        self.player_gp() >= self.vendor_price(Ware::Armor(armor_type))
     }
 
     /// Check if you can afford weapons
@@ -955,12 +1783,12 @@ impl Game {
 
     /// Check if you can afford a specific weapon
     pub fn vendor_can_afford_weapon_type(&self, weapon_type: WeaponType) -> bool {
-        self.player_gp() >= Weapon::cost(weapon_type, true)
+        self.player_gp() >= self.vendor_price(Ware::Weapon(weapon_type))
     }
 
     /// Check if you can afford stats
     pub fn vendor_can_afford_stat(&self) -> bool {
-        self.player_gp() >= 1000
+        self.player_gp() >= self.vendor_price(Ware::Potion(Stat::Strength))
     }
 
     /// Check to see if the player can afford anything from the vendor
@@ -969,9 +1797,173 @@ impl Game {
         self.vendor_can_afford_stat()
     }
 
+    /// Take whatever's useful from a slain vendor's stock
+    ///
+    /// The best armor and weapon replace the player's if they're better,
+    /// equipment is put on if it's an improvement, potions are drunk on the
+    /// spot, and supplies are packed up. What was taken can be had from `take_vendor_loot()`.
+    ///
+    /// Returns true if the player got a lamp
+    fn loot_vendor(&mut self, stock: &Stock) -> bool {
+        self.vendor_loot.clear();
+
+        let best_armor = stock
+            .wares()
+            .iter()
+            .filter_map(|(w, _)| match w {
+                Ware::Armor(a) => Some(*a),
+                _ => None,
+            })
+            .max_by_key(|a| Armor::get_enum_value(*a));
+
+        if let Some(a) = best_armor {
+            if !self.player_has_at_least_armor(a) {
+                self.player.set_armor_by_type(a);
+                self.vendor_loot.push((Ware::Armor(a), 1));
+            }
+        }
+
+        let best_weapon = stock
+            .wares()
+            .iter()
+            .filter_map(|(w, _)| match w {
+                Ware::Weapon(w) => Some(*w),
+                _ => None,
+            })
+            .max_by_key(|w| Weapon::get_enum_value(*w));
+
+        if let Some(w) = best_weapon {
+            if self.player.weapon().damage() < Weapon::damage_by_type(w) {
+                self.player.set_weapon_by_type(w);
+                self.vendor_loot.push((Ware::Weapon(w), 1));
+            }
+        }
+
+        // Most valuable first, so only the best of each kind gets used
+        for &(ware, count) in stock.wares().iter().rev() {
+            match ware {
+                Ware::Equipment(e) if self.equipment_is_upgrade(e) => {
+                    self.player.equip(e);
+                    self.vendor_loot.push((ware, 1));
+                }
+                Ware::Potion(stat) => {
+                    for _ in 0..count {
+                        self.player.change_stat(stat, Game::d(1, 6) as i32);
+                    }
+
+                    self.vendor_loot.push((ware, count));
+                }
+                Ware::Supply(item) => {
+                    self.give_item(item, count);
+                    self.vendor_loot.push((ware, count));
+                }
+                _ => (),
+            }
+        }
+
+        if stock.has(Ware::Lamp) && !self.player.has_lamp() {
+            self.give_item(Item::Lamp, 1);
+            return true;
+        }
+
+        false
+    }
+
+    /// True if a piece of equipment beats what the player has in its place
+    fn equipment_is_upgrade(&self, equipment: Equipment) -> bool {
+        match equipment {
+            Equipment::Shield(s) => {
+                Shield::new(s).block_chance() > self.player.shield().block_chance()
+            }
+            Equipment::Ring(_) => self.player.ring().ring_type() == RingType::None,
+            Equipment::EnchantedWeapon(w, bonus) => {
+                Weapon::enchanted(w, bonus).damage() > self.player.weapon().damage()
+            }
+            Equipment::ElvenArmor(a) => {
+                let current = self.player.armor();
+
+                Armor::get_enum_value(a) > Armor::get_enum_value(current.armor_type())
+                    || (a == current.armor_type() && !current.is_elven())
+            }
+        }
+    }
+
+    /// Return what the player took from the last vendor they slew
+    pub fn take_vendor_loot(&mut self) -> Vec<(Ware, u32)> {
+        std::mem::take(&mut self.vendor_loot)
+    }
+
+    /// Return what the vendor the player is with has to sell
+    pub fn vendor_stock(&self) -> Option<&Stock> {
+        match self.room_at_player().room_type() {
+            RoomType::Monster(m) => m.stock(),
+            _ => None,
+        }
+    }
+
+    /// Return what the vendor the player is with has to sell, mutably
+    fn vendor_stock_mut(&mut self) -> Option<&mut Stock> {
+        self.dungeon
+            .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+            .and_then(|m| m.stock_mut())
+    }
+
+    /// True if the vendor has a ware in stock
+    pub fn vendor_has(&self, ware: Ware) -> bool {
+        self.vendor_stock().is_some_and(|s| s.has(ware))
+    }
+
+    /// Return the gold the vendor has on hand
+    pub fn vendor_purse(&self) -> u32 {
+        self.vendor_stock().map_or(0, |s| s.purse())
+    }
+
+    /// Take payment for some of a ware and take it out of the vendor's stock
+    fn vendor_sell(&mut self, ware: Ware, count: u32) -> Result<(), Error> {
+        if self.state != GameState::Vendor {
+            return Err(Error::WrongState);
+        }
+
+        if self.vendor_stock().map_or(0, |s| s.count(ware)) < count {
+            return Err(Error::OutOfStock);
+        }
+
+        let cost = self.vendor_price(ware) * count;
+
+        self.player.spend(cost)?;
+
+        if let Some(stock) = self.vendor_stock_mut() {
+            for _ in 0..count {
+                stock.take(ware);
+            }
+
+            stock.receive(cost);
+        }
+
+        self.change_vendor_reputation(TRADE_REPUTATION);
+
+        Ok(())
+    }
+
+    /// Buy armor from a vendor
+    pub fn vendor_buy_armor(&mut self, armor_type: ArmorType) -> Result<(), Error> {
+        self.vendor_sell(Ware::Armor(armor_type), 1)?;
+        self.player.set_armor_by_type(armor_type);
+
+        Ok(())
+    }
+
+    /// Buy a weapon from a vendor
+    pub fn vendor_buy_weapon(&mut self, weapon_type: WeaponType) -> Result<(), Error> {
+        self.vendor_sell(Ware::Weapon(weapon_type), 1)?;
+        self.player.set_weapon_by_type(weapon_type);
+
+        Ok(())
+    }
+
     /// Buy stats from a vendor
     pub fn vendor_buy_stat(&mut self, stat: Stat) -> Result<u32, Error> {
-        self.player.spend(1000)?;
+        self.vendor_sell(Ware::Potion(stat), 1)?;
 
         let addition = Game::d(1, 6);
 
@@ -980,64 +1972,257 @@ impl Game {
 
     /// True if the player can buy a lamp from a vendor
     pub fn vendor_can_afford_lamp(&self) -> bool {
-        *self.player.gp() >= Game::vendor_lamp_cost()
+        *self.player.gp() >= self.vendor_price(Ware::Lamp)
     }
 
     /// Return the cost of a lamp from a vendor
-    pub fn vendor_lamp_cost() -> u32 {
-        1000
+    pub fn vendor_lamp_cost(&self) -> u32 {
+        self.vendor_price(Ware::Lamp)
     }
 
     /// Return the cost of a stat from a vendor
-    pub fn vendor_stat_cost() -> u32 {
-        1000
+    pub fn vendor_stat_cost(&self) -> u32 {
+        self.vendor_price(Ware::Potion(Stat::Strength))
     }
 
     /// Buy a lamp from a vendor
     pub fn vendor_buy_lamp(&mut self) -> Result<(), Error> {
-        self.player.spend(1000)?;
+        if !self.player.inventory().can_add(Item::Lamp) {
+            return Err(Error::InventoryFull);
+        }
 
-        self.player.set_lamp(true);
+        self.vendor_sell(Ware::Lamp, 1)?;
 
-        Ok(())
+        self.player.set_lamp(true)
     }
 
-    /// Attack a vendor
-    pub fn vendor_attack(&mut self) {
-        self.vendors_angry = true;
-        self.state = GameState::VendorAttack;
-    }
+    /// Buy some of a consumable item from a vendor
+    pub fn vendor_buy_supply(&mut self, item: Item, count: u32) -> Result<(), Error> {
+        if !self.player.inventory().can_add(item) {
+            return Err(Error::InventoryFull);
+        }
 
-    /// Complete vendor interactions
-    pub fn vendor_complete(&mut self) {
-        self.state = GameState::Move;
-        self.vendor_treasure_price = None;
+        self.vendor_sell(Ware::Supply(item), count)?;
+
+        self.player
+            .inventory_mut()
+            .add(item, count)
+            .expect("checked there was room");
+
+        Ok(())
     }
 
-    /// Check if the player has at least an armor
-    pub fn player_has_at_least_armor(&self, armor_type: ArmorType) -> bool {
-        let player_armor_type = self.player.armor().armor_type();
+    /// Return the cost of having a vendor repair the player's weapon and armor
+    pub fn vendor_repair_cost(&self) -> u32 {
+        let cost = self.player.gear_wear() * self.config.economy.get(Price::Repair);
 
-        Armor::get_enum_value(player_armor_type) >= Armor::get_enum_value(armor_type)
+        self.vendor_charge(cost)
     }
 
-    /// Check if the player has at least a weapon
-    pub fn player_has_at_least_weapon(&self, weapon_type: WeaponType) -> bool {
-        let player_weapon_type = self.player.weapon().weapon_type();
+    /// Have a vendor repair the player's weapon and armor
+    ///
+    /// Returns the GP spent
+    pub fn vendor_repair(&mut self) -> Result<u32, Error> {
+        if self.state != GameState::Vendor {
+            return Err(Error::WrongState);
+        }
 
-        Weapon::get_enum_value(player_weapon_type) >= Weapon::get_enum_value(weapon_type)
+        let cost = self.vendor_repair_cost();
+
+        self.player.spend(cost)?;
+        self.player.repair_gear();
+
+        Ok(cost)
     }
 
-    /// Drink
-    pub fn drink(&mut self) -> Result<DrinkEvent, Error> {
-        let roomtype = self.room_at_player().room_type().clone();
+    /// Return the cost of having a vendor lift the curses it can
+    pub fn vendor_cure_cost(&self) -> u32 {
+        let cost = self
+            .player
+            .curses()
+            .iter()
+            .filter(|c| match self.config.curses.get(c.curse_type) {
+                Some(def) => def.cured_by(CureType::Vendor),
+                None => false,
+            })
+            .map(|c| c.stacks * self.config.economy.get(Price::Cure))
+            .sum();
+
+        self.vendor_charge(cost)
+    }
 
-        if roomtype != RoomType::Pool {
-            return Err(Error::CantGo);
+    /// Have a vendor lift the curses it can
+    ///
+    /// Returns the GP spent
+    pub fn vendor_cure(&mut self) -> Result<u32, Error> {
+        if self.state != GameState::Vendor {
+            return Err(Error::WrongState);
         }
 
-        match Game::d(1, 8) {
-            1 => {
+        let cost = self.vendor_cure_cost();
+
+        self.player.spend(cost)?;
+        self.cure_curses(CureType::Vendor);
+
+        Ok(cost)
+    }
+
+    /// Buy equipment from a vendor
+    pub fn vendor_buy_equipment(&mut self, equipment: Equipment) -> Result<(), Error> {
+        self.vendor_sell(Ware::Equipment(equipment), 1)?;
+        self.player.equip(equipment);
+
+        Ok(())
+    }
+
+    /// Attack a vendor
+    pub fn vendor_attack(&mut self) {
+        if let Some(m) =
+            self.dungeon
+                .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+        {
+            m.set_reputation(-MAX_REPUTATION);
+        }
+
+        self.spread_word(-ATTACK_NEWS);
+
+        self.state = GameState::VendorAttack;
+    }
+
+    /// Return what the vendor the player is with thinks of them
+    pub fn vendor_reputation(&self) -> Option<i32> {
+        match self.room_at_player().room_type() {
+            RoomType::Monster(m) if m.is_vendor() => Some(m.reputation()),
+            _ => None,
+        }
+    }
+
+    /// Change what the vendor the player is with thinks of them
+    fn change_vendor_reputation(&mut self, delta: i32) {
+        if let Some(m) =
+            self.dungeon
+                .monster_at_mut(*self.player.x(), *self.player.y(), *self.player.z())
+        {
+            m.change_reputation(delta);
+        }
+    }
+
+    /// Start word going around about something the player did here
+    fn spread_word(&mut self, change: i32) {
+        self.news.push(News {
+            x: *self.player.x(),
+            y: *self.player.y(),
+            z: *self.player.z(),
+            change,
+            age: 0,
+        });
+    }
+
+    /// Carry news a turn further
+    ///
+    /// Vendors on the same level hear it first, then vendors on the other
+    /// levels, by which time the story has lost some of its sting.
+    fn spread_news(&mut self) {
+        let mut news = std::mem::take(&mut self.news);
+
+        for n in news.iter_mut() {
+            n.age += 1;
+
+            if n.age == NEWS_LEVEL_TURNS {
+                self.tell_vendors(n, false, n.change);
+            }
+
+            if n.age == NEWS_CASTLE_TURNS {
+                self.tell_vendors(n, true, n.change / 2);
+            }
+        }
+
+        news.retain(|n| n.age < NEWS_CASTLE_TURNS);
+
+        self.news = news;
+    }
+
+    /// Change the reputation of every vendor who hears the news, either on
+    /// its own level or on the others
+    fn tell_vendors(&mut self, news: &News, other_levels: bool, change: i32) {
+        for z in 0..self.dungeon_zsize() {
+            if (z != news.z) != other_levels {
+                continue;
+            }
+
+            for y in 0..self.dungeon_ysize() {
+                for x in 0..self.dungeon_xsize() {
+                    if (x, y, z) == (news.x, news.y, news.z) {
+                        continue;
+                    }
+
+                    if let Some(m) = self.dungeon.monster_at_mut(x, y, z) {
+                        if m.is_vendor() {
+                            m.change_reputation(change);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Adjust a price for the vendor's level and what they think of the
+    /// player
+    ///
+    /// Prices run from a quarter off for a friend to a quarter more for a
+    /// customer the vendor can barely stand.
+    fn vendor_charge(&self, cost: u32) -> u32 {
+        let cost = self.config.economy.vendor_price_at(cost, self.player_z());
+        let cost = cost * self.config.difficulty.price_percent() / 100;
+        let reputation = self.vendor_reputation().unwrap_or(0);
+
+        cost * (100 - reputation / 4) as u32 / 100
+    }
+
+    /// Return what the vendor the player is with charges for a ware
+    pub fn vendor_price(&self, ware: Ware) -> u32 {
+        self.vendor_charge(self.config.economy.ware_cost(ware))
+    }
+
+    /// Return the most a vendor on the player's level will pay for a treasure
+    pub fn treasure_max_value(&self, treasure_type: TreasureType) -> u32 {
+        let economy = &self.config.economy;
+
+        economy.treasure_value_at(economy.treasure_max_value(treasure_type), self.player_z())
+    }
+
+    /// Complete vendor interactions
+    pub fn vendor_complete(&mut self) {
+        self.state = GameState::Move;
+        self.vendor_treasure_price = None;
+    }
+
+    /// Check if the player has at least an armor
+    pub fn player_has_at_least_armor(&self, armor_type: ArmorType) -> bool {
+        let player_armor_type = self.player.armor().armor_type();
+
+        Armor::get_enum_value(player_armor_type) >= Armor::get_enum_value(armor_type)
+    }
+
+    /// Check if the player has at least a weapon
+    pub fn player_has_at_least_weapon(&self, weapon_type: WeaponType) -> bool {
+        let player_weapon_type = self.player.weapon().weapon_type();
+
+        Weapon::get_enum_value(player_weapon_type) >= Weapon::get_enum_value(weapon_type)
+    }
+
+    /// Drink
+    pub fn drink(&mut self) -> Result<DrinkEvent, Error> {
+        let roomtype = self.room_at_player().room_type().clone();
+
+        if roomtype != RoomType::Pool {
+            return Err(Error::CantGo);
+        }
+
+        self.cure_curses(CureType::Pool);
+
+        match self.roll_fate(Fate::Pool) {
+            1 => {
                 self.player
                     .change_stat(Stat::Strength, Game::d(1, 3) as i32);
                 Ok(DrinkEvent::Stronger)
@@ -1098,39 +2283,24 @@ impl Game {
     }
 
     /// Shine the lamp
-    pub fn shine_lamp(&mut self, dir: Direction) -> Result<(u32, u32, u32, RoomType), Error> {
-        if !self.player.has_lamp() {
+    pub fn shine_lamp(&mut self, dir: Direction) -> Result<(u32, u32, u32, RememberedRoom), Error> {
+        if !self.player_has_light() {
             return Err(Error::CantGo);
         }
 
-        let (x, y);
-
-        match dir {
-            Direction::North => {
-                x = *self.player.x();
-                y = self.wrap_y(*self.player.y() as i32 - 1);
-            }
-            Direction::South => {
-                x = *self.player.x();
-                y = self.wrap_y(*self.player.y() as i32 + 1);
-            }
-            Direction::West => {
-                x = self.wrap_x(*self.player.x() as i32 - 1);
-                y = *self.player.y();
-            }
-            Direction::East => {
-                x = self.wrap_x(*self.player.x() as i32 + 1);
-                y = *self.player.y();
-            }
+        if self.curse_strength(CurseType::Darkness) > 0 {
+            return Err(Error::Cursed);
         }
 
+        let (x, y) = self.adjacent(dir);
+
         let z = *self.player.z();
 
-        let room = self.dungeon.room_at_mut(x, y, z);
+        self.see_room(x, y, z);
 
-        room.set_discovered(true);
+        let room_type = RememberedRoom::from(self.dungeon.room_at(x, y, z).room_type());
 
-        Ok((x, y, z, room.room_type().clone()))
+        Ok((x, y, z, room_type))
     }
 
     /// Fire a flare from the player location
@@ -1143,7 +2313,7 @@ impl Game {
             return Err(Error::Blind);
         }
 
-        self.player.change_flares(-1);
+        self.player.use_flares(1);
 
         let xm1 = *self.player.x() as i32 - 1;
         let ym1 = *self.player.y() as i32 - 1;
@@ -1156,7 +2326,7 @@ impl Game {
             for x in xm1..(xm1 + 3) {
                 let xw = self.wrap_x(x);
 
-                self.dungeon.room_at_mut(xw, yw, z).set_discovered(true);
+                self.see_room(xw, yw, z);
             }
         }
 
@@ -1165,21 +2335,23 @@ impl Game {
 
     /// Gaze into an Orb
     pub fn gaze(&mut self) -> Result<OrbEvent, Error> {
-        {
-            let room_type = self.room_at_player().room_type();
+        let at_orb = *self.room_at_player().room_type() == RoomType::CrystalOrb;
 
-            if *room_type != RoomType::CrystalOrb {
-                return Err(Error::CantGo);
-            }
+        if !at_orb && !self.player.has_treasure(TreasureType::Palantir) {
+            return Err(Error::CantGo);
         }
 
         if self.player.is_blind() {
             return Err(Error::Blind);
         }
 
+        if !at_orb {
+            return self.gaze_palantir();
+        }
+
         let mut rng = rng();
 
-        match Game::d(1, 6) {
+        match self.roll_fate(Fate::Gaze) {
             1 => {
                 self.player
                     .change_stat(Stat::Strength, -(Game::d(1, 2) as i32));
@@ -1187,18 +2359,18 @@ impl Game {
                 Ok(OrbEvent::BloodyHeap)
             }
 
-            2 => Ok(OrbEvent::Polymorph(Game::rand_monster_type())),
+            2 => Ok(OrbEvent::Polymorph(self.rand_monster_type())),
 
-            3 => Ok(OrbEvent::GazeBack(Game::rand_monster_type())),
+            3 => Ok(OrbEvent::GazeBack(self.rand_monster_type())),
 
             4 => {
                 let x = rng.random_range(0..*self.dungeon.xsize());
                 let y = rng.random_range(0..*self.dungeon.ysize());
                 let z = rng.random_range(0..*self.dungeon.zsize());
 
-                let room_type = self.dungeon.room_at(x, y, z).room_type().clone();
+                let room_type = RememberedRoom::from(self.dungeon.room_at(x, y, z).room_type());
 
-                self.dungeon.room_at_mut(x, y, z).set_discovered(true);
+                self.see_room(x, y, z);
 
                 Ok(OrbEvent::Item(room_type, x, y, z))
             }
@@ -1228,6 +2400,106 @@ impl Game {
         }
     }
 
+    /// Gaze into the Palantir, which only ever shows true visions
+    fn gaze_palantir(&mut self) -> Result<OrbEvent, Error> {
+        if let Some(turn) = self.palantir_turn {
+            if self.turn < turn + PALANTIR_TURNS {
+                return Err(Error::TooSoon);
+            }
+        }
+
+        self.palantir_turn = Some(self.turn);
+
+        let (x, y, z) = self.dungeon.orb_of_zot_location();
+
+        if z == *self.player.z() && Game::d(1, PALANTIR_ORB_CHANCE) == 1 {
+            return Ok(OrbEvent::OrbOfZot(x, y, z));
+        }
+
+        let mut rng = rng();
+
+        let x = rng.random_range(0..*self.dungeon.xsize());
+        let y = rng.random_range(0..*self.dungeon.ysize());
+        let z = rng.random_range(0..*self.dungeon.zsize());
+
+        let room_type = RememberedRoom::from(self.dungeon.room_at(x, y, z).room_type());
+
+        self.see_room(x, y, z);
+
+        Ok(OrbEvent::Item(room_type, x, y, z))
+    }
+
+    /// Roll the die for a random event, and roll the next one ahead of time
+    fn roll_fate(&mut self, fate: Fate) -> u32 {
+        self.fates
+            .insert(fate, Game::fate_die(fate, self.config.difficulty))
+            .expect("every fate is rolled ahead")
+    }
+
+    /// Roll the die for a random event
+    ///
+    /// Depending on the difficulty, a pool's harmful or helpful drinks may
+    /// get a second roll.
+    fn fate_die(fate: Fate, difficulty: Difficulty) -> u32 {
+        let roll = Game::d(1, fate.sides());
+
+        if fate != Fate::Pool {
+            return roll;
+        }
+
+        let luck = difficulty.pool_luck();
+
+        let reroll = match roll {
+            2 | 4 | 6 => luck > 0, // Weaker, dumber, or clumsier
+            1 | 3 | 5 => luck < 0, // Stronger, smarter, or nimbler
+            _ => false,
+        };
+
+        if reroll && Game::d(1, 100) <= luck.unsigned_abs() {
+            Game::d(1, fate.sides())
+        } else {
+            roll
+        }
+    }
+
+    /// Return what the Norn Stone foretells about the current room, if the
+    /// player has it and there's anything to foretell
+    pub fn omen(&self) -> Option<Omen> {
+        if !self.player.has_treasure(TreasureType::NornStone) {
+            return None;
+        }
+
+        match self.room_at_player().room_type() {
+            RoomType::Chest => Some(match self.fates[&Fate::Chest] {
+                1 => Omen::ChestExplodes,
+                2 => Omen::ChestGas,
+                3 => Omen::ChestGold,
+                _ => Omen::ChestEquipment,
+            }),
+            RoomType::Pool => Some(Omen::Drink(match self.fates[&Fate::Pool] {
+                1 => DrinkEvent::Stronger,
+                2 => DrinkEvent::Weaker,
+                3 => DrinkEvent::Smarter,
+                4 => DrinkEvent::Dumber,
+                5 => DrinkEvent::Nimbler,
+                6 => DrinkEvent::Clumsier,
+                7 => DrinkEvent::ChangeRace,
+                _ => DrinkEvent::ChangeGender,
+            })),
+            RoomType::CrystalOrb => Some(match self.fates[&Fate::Gaze] {
+                1 => Omen::GazeHarms,
+                4 | 5 => Omen::GazeVision,
+                _ => Omen::GazeNothing,
+            }),
+            _ => None,
+        }
+    }
+
+    /// True if the player has a lamp or something else to shine
+    pub fn player_has_light(&self) -> bool {
+        self.player.has_lamp() || self.player.has_treasure(TreasureType::Silmaril)
+    }
+
     /// Open a book
     pub fn open_book(&mut self) -> Result<BookEvent, Error> {
         {
@@ -1238,7 +2510,7 @@ impl Game {
             }
         }
 
-        self.make_current_room_empty();
+        self.clear_current_room();
 
         match Game::d(1, 6) {
             1 => {
@@ -1246,7 +2518,7 @@ impl Game {
                 Ok(BookEvent::Blind)
             }
             2 => Ok(BookEvent::Poetry),
-            3 => Ok(BookEvent::PlayMonster(Game::rand_monster_type())),
+            3 => Ok(BookEvent::PlayMonster(self.rand_monster_type())),
             4 => {
                 self.player.set_stat(Stat::Dexterity, 18);
                 Ok(BookEvent::Dexterity)
@@ -1273,11 +2545,15 @@ impl Game {
             }
         }
 
-        // In the original game, gas would not destroy the chest.
-        // We mod that here to destroy the chest in all cases.
-        self.make_current_room_empty();
+        let fate = self.roll_fate(Fate::Chest);
 
-        match Game::d(1, 4) {
+        // In the original game, gas would not destroy the chest. Modern rules
+        // destroy the chest in all cases.
+        if fate != 2 || self.config.rules.gas_destroys_chest {
+            self.clear_current_room();
+        }
+
+        match fate {
             1 => {
                 if self.player.damage_st(Game::d(1, 6)) {
                     self.state = GameState::Dead;
@@ -1289,6 +2565,11 @@ impl Game {
                 self.move_dir(Game::rand_direction());
                 Ok(ChestEvent::Gas)
             }
+            4 if self.config.rules.chest_gear => {
+                let equipment = Game::rand_equipment();
+                self.found_equipment = Some(equipment);
+                Ok(ChestEvent::Equipment(equipment))
+            }
             3..=4 => {
                 let gold = Game::d(1, 1000);
                 self.player.add_gp(gold as i32);
@@ -1298,136 +2579,548 @@ impl Game {
         }
     }
 
-    /// Cure blindness
+    /// Give the player some of an item, leaving it on the floor if they can't
+    /// carry it
     ///
-    /// True if blindness was cured
-    pub fn cure_blindness(&mut self) -> bool {
-        if self.player.is_blind() && self.player.has_treasure(TreasureType::OpalEye) {
-            self.player.set_blind(false);
-            true
-        } else {
-            false
+    /// Returns true if the player carries it
+    fn give_item(&mut self, item: Item, count: u32) -> bool {
+        if self.player.inventory_mut().add(item, count).is_ok() {
+            return true;
         }
-    }
 
-    /// Cure book stuck to hands
-    ///
-    /// True if the book was dissolved
-    pub fn cure_book(&mut self) -> bool {
-        if *self.player.book_stuck() && self.player.has_treasure(TreasureType::BlueFlame) {
-            self.player.set_book_stuck(false);
-            true
-        } else {
-            false
-        }
+        let (x, y, z) = (*self.player.x(), *self.player.y(), *self.player.z());
+
+        self.dungeon
+            .room_at_mut(x, y, z)
+            .items_mut()
+            .add(item, count)
+            .expect("rooms hold any number of items");
+
+        false
     }
 
-    /// Handle curses
-    pub fn curse_effects(&mut self) {
-        if self.player.has_curse(CurseType::Lethargy) {
-            if !self.player.has_treasure(TreasureType::RubyRed) {
-                self.lethargic = true;
-                self.turn += 1; // additional turn count per turn
-            } else {
-                self.lethargic = false;
-            }
+    /// Use up one of an item the player is carrying
+    pub fn use_item(&mut self, item: Item) -> Result<UseEvent, Error> {
+        if self.state != GameState::Move && self.state != GameState::Init {
+            return Err(Error::WrongState);
         }
 
-        if self.player.has_curse(CurseType::Forgetfulness)
-            && !self.player.has_treasure(TreasureType::GreenGem)
-        {
-            self.rand_mark_unexplored();
+        if !item.is_usable() {
+            return Err(Error::CantGo);
         }
 
-        if self.player.has_curse(CurseType::TheLeech)
-            && !self.player.has_treasure(TreasureType::PalePearl)
-        {
-            self.player.add_gp(-(Game::d(1, 5) as i32));
+        if !self.player.inventory().has(item) {
+            return Err(Error::NotCarrying);
         }
-    }
 
-    /// Check for catching a curse
-    pub fn curse_check(&mut self) -> bool {
-        let curse = *self.room_at_player().curse();
+        let event = match item {
+            Item::HealingPotion => {
+                if *self.player.stat(&Stat::Strength) >= 18 {
+                    return Err(Error::NotNeeded);
+                }
 
-        if curse != CurseType::None && !self.player.has_curse(curse) {
-            self.player.add_curse(curse);
-            true
-        } else {
-            false
-        }
-    }
+                let before = *self.player.stat(&Stat::Strength);
 
-    /// Choose a random message
-    pub fn rand_message(&self) -> RandomMessage {
-        if Game::d(1, 5) != 1 {
-            return RandomMessage::None;
-        }
+                let after = self
+                    .player
+                    .change_stat(Stat::Strength, Game::d(1, 6) as i32 + 2);
 
-        let mut msgs = vec![
-            RandomMessage::HearSound,
-            RandomMessage::Sneeze,
-            RandomMessage::StepFrog,
-            RandomMessage::MonsterFrying,
-            RandomMessage::Watched,
-            RandomMessage::Playing,
-        ];
+                UseEvent::Healed(after - before)
+            }
+            Item::SightElixir => {
+                if !self.player.is_blind() {
+                    return Err(Error::NotNeeded);
+                }
 
-        // In the original game, "YOU SEE A BAT" was replaced by "YOU STEPPED ON
-        // A FROG" if the player was blind. Instead, here we just don't show the
-        // "SEE" messages if the player is blind.
+                self.player.set_blind(false);
 
-        if !self.player.is_blind() {
-            msgs.push(RandomMessage::SeeBat);
-        }
+                UseEvent::Sight
+            }
+            Item::MappingScroll => {
+                if self.player.is_blind() {
+                    return Err(Error::Blind);
+                }
 
-        let mut rng = rng();
+                let z = *self.player.z();
 
-        let i = rng.random_range(0..msgs.len());
+                for y in 0..self.dungeon_ysize() {
+                    for x in 0..self.dungeon_xsize() {
+                        self.see_room(x, y, z);
+                    }
+                }
 
-        msgs[i]
-    }
+                UseEvent::Mapped(z)
+            }
+            Item::Solvent => {
+                if !*self.player.book_stuck() {
+                    return Err(Error::NotNeeded);
+                }
 
-    /// Tell the caller if it's time for a random recipe
-    pub fn rand_recipe(&mut self) -> bool {
-        if self.last_recipe_turn == 0 || self.turn - self.last_recipe_turn > 60 {
-            self.last_recipe_turn = self.turn;
-            true
-        } else {
-            false
-        }
-    }
+                self.player.set_book_stuck(false);
 
-    /// Quit the game
-    pub fn quit(&mut self) {
-        self.state = GameState::Quit;
+                UseEvent::Unstuck
+            }
+            _ => unreachable!("only usable items get this far"),
+        };
+
+        self.player.inventory_mut().remove(item, 1);
+
+        Ok(event)
     }
 
-    /// Roll a die (1d6, 2d7, etc.)
-    pub fn d(count: u32, sides: u32) -> u32 {
-        let mut total = 0;
+    /// Drop some of an item in the current room
+    ///
+    /// Returns the number dropped
+    pub fn drop_item(&mut self, item: Item, count: u32) -> Result<u32, Error> {
+        if self.state != GameState::Move && self.state != GameState::Init {
+            return Err(Error::WrongState);
+        }
 
-        let mut rng = rng();
+        let dropped = self.player.inventory_mut().remove(item, count);
 
-        for _ in 0..count {
-            total += rng.random_range(0..sides) + 1;
+        if dropped == 0 {
+            return Err(Error::NotCarrying);
         }
 
-        total
-    }
+        let (x, y, z) = (*self.player.x(), *self.player.y(), *self.player.z());
 
-    /// Return game state
-    pub fn state(&self) -> GameState {
-        self.state
-    }
+        self.dungeon
+            .room_at_mut(x, y, z)
+            .items_mut()
+            .add(item, dropped)
+            .expect("rooms hold any number of items");
 
-    /// Accessors for player position
-    pub fn player_x(&self) -> u32 {
-        *self.player.x()
+        Ok(dropped)
     }
 
-    /// Accessors for player position
-    pub fn player_y(&self) -> u32 {
+    /// Make sure the player can reach the stash
+    fn check_stash(&self) -> Result<(), Error> {
+        if self.state != GameState::Move && self.state != GameState::Init {
+            return Err(Error::WrongState);
+        }
+
+        if *self.room_at_player().room_type() != RoomType::Entrance {
+            return Err(Error::NotAtEntrance);
+        }
+
+        Ok(())
+    }
+
+    /// Bank some gold at the entrance
+    pub fn stash_deposit_gp(&mut self, amount: u32) -> Result<(), Error> {
+        self.check_stash()?;
+
+        self.player.spend(amount)?;
+        self.stash_gp += amount;
+
+        Ok(())
+    }
+
+    /// Take some banked gold back out
+    pub fn stash_withdraw_gp(&mut self, amount: u32) -> Result<(), Error> {
+        self.check_stash()?;
+
+        if amount > self.stash_gp {
+            return Err(Error::NotEnoughGP);
+        }
+
+        self.stash_gp -= amount;
+        self.player.add_gp(amount as i32);
+
+        Ok(())
+    }
+
+    /// Bank a treasure at the entrance
+    pub fn stash_deposit_treasure(&mut self, treasure_type: TreasureType) -> Result<(), Error> {
+        self.check_stash()?;
+
+        if !self.player.remove_treasure(treasure_type) {
+            return Err(Error::NotCarrying);
+        }
+
+        self.stash
+            .add(Item::Treasure(treasure_type), 1)
+            .expect("the stash holds any number of items");
+
+        Ok(())
+    }
+
+    /// Take a banked treasure back out
+    pub fn stash_withdraw_treasure(&mut self, treasure_type: TreasureType) -> Result<(), Error> {
+        self.check_stash()?;
+
+        let item = Item::Treasure(treasure_type);
+
+        if !self.stash.has(item) {
+            return Err(Error::NotHere);
+        }
+
+        self.player.inventory_mut().add(item, 1)?;
+        self.stash.remove(item, 1);
+
+        Ok(())
+    }
+
+    /// Return the gold banked at the entrance
+    pub fn stash_gp(&self) -> u32 {
+        self.stash_gp
+    }
+
+    /// Return the treasures banked at the entrance
+    pub fn stash_treasures(&self) -> Vec<TreasureType> {
+        self.stash
+            .stacks()
+            .iter()
+            .filter_map(|s| match s.item {
+                Item::Treasure(t) => Some(t),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Return the treasures the player ends the game with
+    ///
+    /// Anything in the stash only counts if the player walked out of the
+    /// castle to collect it.
+    pub fn final_treasures(&self) -> Vec<TreasureType> {
+        let mut treasures = self.player.get_treasures();
+
+        if self.state() == GameState::Exit {
+            treasures.extend(self.stash_treasures());
+        }
+
+        treasures
+    }
+
+    /// Return the gold the player ends the game with, counting the stash if
+    /// they walked out
+    pub fn final_gp(&self) -> u32 {
+        match self.state() {
+            GameState::Exit => *self.player.gp() + self.stash_gp,
+            _ => *self.player.gp(),
+        }
+    }
+
+    /// Pick up some of an item from the current room
+    ///
+    /// Returns the number taken
+    pub fn take_item(&mut self, item: Item, count: u32) -> Result<u32, Error> {
+        if self.state != GameState::Move && self.state != GameState::Init {
+            return Err(Error::WrongState);
+        }
+
+        let (x, y, z) = (*self.player.x(), *self.player.y(), *self.player.z());
+
+        let available = self.dungeon.room_at(x, y, z).items().count(item);
+
+        if available == 0 {
+            return Err(Error::NotHere);
+        }
+
+        let count = count.min(available);
+
+        self.player.inventory_mut().add(item, count)?;
+
+        self.dungeon
+            .room_at_mut(x, y, z)
+            .items_mut()
+            .remove(item, count);
+
+        Ok(count)
+    }
+
+    /// Return the items lying in the current room
+    pub fn room_items(&self) -> Vec<Stack> {
+        self.room_at_player().items().stacks().to_vec()
+    }
+
+    /// Return what the player is carrying
+    pub fn player_inventory(&self) -> Vec<Stack> {
+        self.player.inventory().stacks().to_vec()
+    }
+
+    /// Return the number of kinds of item the player can carry, if limited
+    pub fn player_inventory_capacity(&self) -> Option<usize> {
+        if self.config.rules.pack_limit {
+            Some(self.player.inventory().capacity())
+        } else {
+            None
+        }
+    }
+
+    /// Return a random piece of equipment
+    fn rand_equipment() -> Equipment {
+        match Game::d(1, 4) {
+            1 => Equipment::Shield(match Game::d(1, 3) {
+                1 => ShieldType::Buckler,
+                2 => ShieldType::Kite,
+                _ => ShieldType::Tower,
+            }),
+            2 => Equipment::Ring(match Game::d(1, 3) {
+                1 => RingType::Protection,
+                2 => RingType::Accuracy,
+                _ => RingType::Regeneration,
+            }),
+            3 => {
+                let weapon_type = match Game::d(1, 3) {
+                    1 => WeaponType::Dagger,
+                    2 => WeaponType::Mace,
+                    _ => WeaponType::Sword,
+                };
+
+                Equipment::EnchantedWeapon(weapon_type, Game::d(1, 2))
+            }
+            _ => Equipment::ElvenArmor(match Game::d(1, 3) {
+                1 => ArmorType::Leather,
+                2 => ArmorType::Chainmail,
+                _ => ArmorType::Plate,
+            }),
+        }
+    }
+
+    /// Leave the equipment found in the last chest behind
+    pub fn decline_found_equipment(&mut self) {
+        self.found_equipment = None;
+    }
+
+    /// Put on the equipment found in the last chest, replacing what's in its
+    /// slot
+    pub fn take_found_equipment(&mut self) -> Result<Equipment, Error> {
+        match self.found_equipment.take() {
+            Some(equipment) => {
+                self.player.equip(equipment);
+                Ok(equipment)
+            }
+            None => Err(Error::WrongState),
+        }
+    }
+
+    /// Cure blindness
+    ///
+    /// True if blindness was cured
+    pub fn cure_blindness(&mut self) -> bool {
+        if self.player.is_blind() && self.player.has_treasure(TreasureType::OpalEye) {
+            self.player.set_blind(false);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Cure book stuck to hands
+    ///
+    /// True if the book was dissolved
+    pub fn cure_book(&mut self) -> bool {
+        if *self.player.book_stuck() && self.player.has_treasure(TreasureType::BlueFlame) {
+            self.player.set_book_stuck(false);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Return how strongly a curse affects the player, 0 if they don't have
+    /// it or carry its ward
+    fn curse_strength(&self, curse: CurseType) -> u32 {
+        let warded = match self.config.curses.get(curse) {
+            Some(def) => match def.ward {
+                Some(t) => self.player.has_treasure(t),
+                None => false,
+            },
+            None => false,
+        };
+
+        if warded {
+            0
+        } else {
+            self.player.curse_stacks(curse)
+        }
+    }
+
+    /// Handle curses
+    pub fn curse_effects(&mut self) {
+        let lethargy = self.curse_strength(CurseType::Lethargy);
+
+        self.lethargic = lethargy > 0;
+        self.turn += lethargy; // additional turn count per turn
+
+        self.check_turn_limit();
+
+        for _ in 0..self.curse_strength(CurseType::Forgetfulness) {
+            self.rand_mark_unexplored();
+        }
+
+        // The stash at the entrance is out of the leech's reach
+        for _ in 0..self.curse_strength(CurseType::TheLeech) {
+            self.player.add_gp(-(Game::d(1, 5) as i32));
+        }
+
+        for _ in 0..self.curse_strength(CurseType::Clumsiness) {
+            if *self.player.stat(&Stat::Dexterity) > 1 && Game::d(1, CLUMSY_CHANCE) == 1 {
+                self.player.change_stat(Stat::Dexterity, -1);
+            }
+        }
+
+        let expired = self.player.tick_curses();
+        self.lifted_curses.extend(expired);
+    }
+
+    /// Check for catching a curse
+    ///
+    /// Under the `curses_leave_rooms` rule catching a curse takes it out of
+    /// the room. Otherwise the curse stays put, and a player who already has
+    /// it doesn't catch it again. Returns the curse caught, if any.
+    pub fn curse_check(&mut self) -> Option<CurseType> {
+        let curse = *self.room_at_player().curse();
+
+        let def = self.config.curses.get(curse)?;
+
+        if !self.config.rules.curses_leave_rooms {
+            if self.player.has_curse(curse) {
+                return None;
+            }
+
+            self.player.add_curse(def);
+
+            return Some(curse);
+        }
+
+        self.player.add_curse(def);
+
+        let (x, y, z) = (*self.player.x(), *self.player.y(), *self.player.z());
+        self.dungeon.room_at_mut(x, y, z).set_curse(CurseType::None);
+
+        Some(curse)
+    }
+
+    /// Lift every curse on the player that can be cured a given way
+    ///
+    /// Returns the number of curses lifted
+    fn cure_curses(&mut self, cure: CureType) -> u32 {
+        let curable: Vec<CurseType> = self
+            .player
+            .curses()
+            .iter()
+            .map(|c| c.curse_type)
+            .filter(|&c| match self.config.curses.get(c) {
+                Some(def) => def.cured_by(cure),
+                None => false,
+            })
+            .collect();
+
+        for &c in &curable {
+            self.player.remove_curse(c);
+        }
+
+        self.lifted_curses.extend(&curable);
+
+        curable.len() as u32
+    }
+
+    /// Return the curses lifted since the last call
+    pub fn take_lifted_curses(&mut self) -> Vec<CurseType> {
+        std::mem::take(&mut self.lifted_curses)
+    }
+
+    /// Return the curses on the player
+    pub fn player_curses(&self) -> Vec<Curse> {
+        self.player.curses().to_vec()
+    }
+
+    /// Choose a random message
+    pub fn rand_message(&self) -> RandomMessage {
+        if Game::d(1, 5) != 1 {
+            return RandomMessage::None;
+        }
+
+        let mut msgs = vec![
+            RandomMessage::HearSound,
+            RandomMessage::Sneeze,
+            RandomMessage::StepFrog,
+            RandomMessage::MonsterFrying,
+            RandomMessage::Watched,
+            RandomMessage::Playing,
+        ];
+
+        // In the original game, "YOU SEE A BAT" was replaced by "YOU STEPPED ON
+        // A FROG" if the player was blind. Modern rules just don't show the
+        // "SEE" messages if the player is blind.
+
+        if !self.player.is_blind() {
+            msgs.push(RandomMessage::SeeBat);
+        } else if !self.config.rules.hide_bat_when_blind {
+            msgs.push(RandomMessage::StepFrog);
+        }
+
+        let mut rng = rng();
+
+        let i = rng.random_range(0..msgs.len());
+
+        msgs[i]
+    }
+
+    /// Tell the caller if it's time for a random recipe
+    pub fn rand_recipe(&mut self) -> bool {
+        if self.last_recipe_turn == 0 || self.turn - self.last_recipe_turn > 60 {
+            self.last_recipe_turn = self.turn;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Quit the game
+    pub fn quit(&mut self) {
+        self.state = GameState::Quit;
+    }
+
+    /// Roll a die (1d6, 2d7, etc.)
+    pub fn d(count: u32, sides: u32) -> u32 {
+        let mut total = 0;
+
+        let mut rng = rng();
+
+        for _ in 0..count {
+            total += rng.random_range(0..sides) + 1;
+        }
+
+        total
+    }
+
+    /// Return the options this game was created with
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Return the definition of a kind of monster
+    pub fn monster_def(&self, monster_type: MonsterType) -> &MonsterDef {
+        self.config
+            .monsters
+            .get(monster_type)
+            .expect("monster type not in the monster table")
+    }
+
+    /// Return all the kinds of monster that can be placed, weakest first
+    pub fn monster_defs(&self) -> Vec<&MonsterDef> {
+        self.config.monsters.spawnable()
+    }
+
+    /// Return game state
+    pub fn state(&self) -> GameState {
+        if self.out_of_time && self.state != GameState::Dead {
+            return GameState::OutOfTime;
+        }
+
+        self.state
+    }
+
+    /// Accessors for player position
+    pub fn player_x(&self) -> u32 {
+        *self.player.x()
+    }
+
+    /// Accessors for player position
+    pub fn player_y(&self) -> u32 {
         *self.player.y()
     }
 
@@ -1478,6 +3171,36 @@ impl Game {
         self.player.weapon().weapon_type()
     }
 
+    /// Accessor for player weapon enchantment, 0 for a plain weapon
+    pub fn player_weapon_bonus(&self) -> u32 {
+        self.player.weapon().bonus()
+    }
+
+    /// True if the player's armor is elven
+    pub fn player_armor_is_elven(&self) -> bool {
+        self.player.armor().is_elven()
+    }
+
+    /// Accessor for player shield type
+    pub fn player_shield_type(&self) -> ShieldType {
+        self.player.shield().shield_type()
+    }
+
+    /// Accessor for player ring type
+    pub fn player_ring_type(&self) -> RingType {
+        self.player.ring().ring_type()
+    }
+
+    /// Condition of the player's weapon as a percentage, or None for no weapon
+    pub fn player_weapon_condition(&self) -> Option<u32> {
+        self.player.weapon().condition()
+    }
+
+    /// Condition of the player's armor as a percentage, or None for no armor
+    pub fn player_armor_condition(&self) -> Option<u32> {
+        self.player.armor().condition()
+    }
+
     /// Accessor for player lamp
     pub fn player_has_lamp(&self) -> bool {
         self.player.has_lamp()
@@ -1490,7 +3213,7 @@ impl Game {
 
     /// Init the player
     pub fn player_init(&mut self, race: Race) {
-        self.player.init(race);
+        self.player.init(race, self.config.difficulty);
     }
 
     /// Set player's gender
@@ -1505,32 +3228,71 @@ impl Game {
 
     /// Give the player some armor
     pub fn player_purchase_armor(&mut self, a: ArmorType, is_vendor: bool) -> Result<(), Error> {
-        self.player.purchase_armor(a, is_vendor)
+        self.player
+            .spend(self.config.economy.armor_cost(a, is_vendor))?;
+        self.player.set_armor_by_type(a);
+
+        Ok(())
     }
 
     /// Give the player a weapon
     pub fn player_purchase_weapon(&mut self, w: WeaponType, is_vendor: bool) -> Result<(), Error> {
-        self.player.purchase_weapon(w, is_vendor)
+        self.player
+            .spend(self.config.economy.weapon_cost(w, is_vendor))?;
+        self.player.set_weapon_by_type(w);
+
+        Ok(())
     }
 
     /// True if the player can afford a lamp
     pub fn player_can_purchase_lamp(&self) -> bool {
-        self.player.can_purchase_lamp()
+        *self.player.gp() >= self.config.economy.get(Price::StartLamp)
     }
 
     /// Purchase a lamp
     pub fn player_purchase_lamp(&mut self, lamp: bool) -> Result<(), Error> {
-        self.player.purchase_lamp(lamp)
+        if !self.player_can_purchase_lamp() {
+            return Err(Error::NotEnoughGP);
+        }
+
+        if lamp {
+            if !self.player.inventory().can_add(Item::Lamp) {
+                return Err(Error::InventoryFull);
+            }
+
+            self.player
+                .spend(self.config.economy.get(Price::StartLamp))?;
+            self.player.set_lamp(true)?;
+        } else {
+            self.player.set_lamp(false)?;
+        }
+
+        Ok(())
     }
 
     /// Return the max number of flares a player can afford
     pub fn player_max_flares(&self) -> u32 {
-        self.player.max_flares()
+        match self.config.economy.get(Price::Flare) {
+            0 => u32::MAX,
+            cost => *self.player.gp() / cost,
+        }
     }
 
     /// Purchase flares
     pub fn player_purchase_flares(&mut self, flares: u32) -> Result<(), Error> {
-        self.player.purchase_flares(flares)
+        if flares > self.player_max_flares() {
+            return Err(Error::NotEnoughGP);
+        }
+
+        if flares > 0 && !self.player.inventory().can_add(Item::Flare) {
+            return Err(Error::InventoryFull);
+        }
+
+        self.player
+            .spend(flares * self.config.economy.get(Price::Flare))?;
+        self.player.inventory_mut().add(Item::Flare, flares)?;
+
+        Ok(())
     }
 
     /// Return true if the player is blind
@@ -1573,16 +3335,6 @@ impl Game {
         *self.dungeon.zsize()
     }
 
-    /// Return a reference to the room at a location
-    pub fn dungeon_room_at(&self, x: u32, y: u32, z: u32) -> &Room {
-        self.dungeon.room_at(x, y, z)
-    }
-
-    /// Return a mutable reference to the room at a location
-    pub fn dungeon_room_at_mut(&mut self, x: u32, y: u32, z: u32) -> &Room {
-        self.dungeon.room_at_mut(x, y, z)
-    }
-
     /// Get character gender
     pub fn player_gender(&self) -> &Gender {
         self.player.gender()
@@ -1594,13 +3346,181 @@ impl Game {
     }
 
     /// Add to turns
-    pub fn add_turn(&mut self, amount: u32) {
-        self.turn += amount;
+    ///
+    /// Monsters recover and, if they wander, get to move once for every turn.
+    /// Returns true if one of them came into the player's room.
+    pub fn add_turn(&mut self, amount: u32) -> bool {
+        let mut arrived = false;
+
+        for _ in 0..amount {
+            self.turn += 1;
+
+            self.monsters_recover();
+            self.spread_news();
+
+            if self.player.ring().regenerates() && self.turn.is_multiple_of(RING_REGEN_TURNS) {
+                self.player.change_stat(Stat::Strength, 1);
+            }
+
+            if self.config.wandering {
+                arrived |= self.wander_monsters();
+            }
+        }
+
+        self.check_turn_limit();
+
+        arrived
+    }
+
+    /// End the game if the player has used up all their turns
+    ///
+    /// This sticks, so whatever else happens during the same action, like
+    /// walking out the door after a chest of gas, the game is still lost.
+    fn check_turn_limit(&mut self) {
+        let Some(limit) = self.config.turn_limit else {
+            return;
+        };
+
+        if self.turn > limit && !matches!(self.state, GameState::Dead | GameState::Exit) {
+            self.state = GameState::OutOfTime;
+            self.out_of_time = true;
+        }
+    }
+
+    /// Return the most turns the game can last, if there's a limit
+    pub fn turn_limit(&self) -> Option<u32> {
+        self.config.turn_limit
+    }
+
+    /// Let the monsters recover a little from fighting the player
+    ///
+    /// If regeneration is on, wounded monsters heal a hitpoint every few
+    /// turns. Webs only weaken in combat, so a webbed monster stays that way
+    /// until the player comes back.
+    fn monsters_recover(&mut self) {
+        if !self.config.regeneration || !self.turn.is_multiple_of(REGEN_TURNS) {
+            return;
+        }
+
+        for monster in self.dungeon.monsters_mut() {
+            monster.heal(1);
+        }
+    }
+
+    /// Give the monsters on the player's level a chance to move
+    ///
+    /// Monsters only move into empty rooms. Those close to the player tend to
+    /// follow them. Vendors and webbed monsters stay put, as does any monster
+    /// already in the player's room.
+    ///
+    /// Returns true if a monster came into the player's room.
+    fn wander_monsters(&mut self) -> bool {
+        let px = *self.player.x();
+        let py = *self.player.y();
+        let z = *self.player.z();
+
+        let xsize = self.dungeon_xsize();
+        let ysize = self.dungeon_ysize();
+
+        let mut wanderers = Vec::new();
+
+        for y in 0..ysize {
+            for x in 0..xsize {
+                if (x, y) == (px, py) {
+                    continue;
+                }
+
+                if let RoomType::Monster(m) = self.dungeon.room_at(x, y, z).room_type() {
+                    if !m.is_vendor() && *m.webbed() == 0 {
+                        wanderers.push((x, y));
+                    }
+                }
+            }
+        }
+
+        let mut arrived = false;
+
+        for (x, y) in wanderers {
+            if Game::d(1, WANDER_CHANCE) != 1 {
+                continue;
+            }
+
+            // Distance to the player each way, taking wraparound into account
+            let dx = Game::wrapped_offset(x, px, xsize);
+            let dy = Game::wrapped_offset(y, py, ysize);
+
+            let dir = if dx.abs() + dy.abs() <= FOLLOW_DISTANCE && Game::d(1, 2) == 1 {
+                // Follow the player
+                if dx.abs() > dy.abs() {
+                    if dx > 0 {
+                        Direction::East
+                    } else {
+                        Direction::West
+                    }
+                } else if dy > 0 {
+                    Direction::South
+                } else {
+                    Direction::North
+                }
+            } else {
+                Game::rand_direction()
+            };
+
+            let (nx, ny) = match dir {
+                Direction::North => (x, self.wrap_y(y as i32 - 1)),
+                Direction::South => (x, self.wrap_y(y as i32 + 1)),
+                Direction::West => (self.wrap_x(x as i32 - 1), y),
+                Direction::East => (self.wrap_x(x as i32 + 1), y),
+            };
+
+            if *self.dungeon.room_at(nx, ny, z).room_type() != RoomType::Empty {
+                continue;
+            }
+
+            // Only one monster at a time gets into the player's room
+            if (nx, ny) == (px, py) {
+                if arrived {
+                    continue;
+                }
+
+                arrived = true;
+            }
+
+            self.dungeon.move_monster((x, y), (nx, ny), z);
+        }
+
+        if arrived {
+            self.see_room(px, py, z);
+        }
+
+        arrived
+    }
+
+    /// Return the shortest signed distance from one coordinate to another on a
+    /// level that wraps around
+    fn wrapped_offset(from: u32, to: u32, size: u32) -> i32 {
+        let offset = (to as i32 - from as i32).rem_euclid(size as i32);
+
+        if offset > size as i32 / 2 {
+            offset - size as i32
+        } else {
+            offset
+        }
     }
 
-    /// True if the vendors are angry
-    pub fn vendors_angry(&self) -> bool {
-        self.vendors_angry
+    /// Return exploration and content counts for a level
+    pub fn level_stats(&self, z: u32) -> Stats {
+        self.dungeon.level_stats(z)
+    }
+
+    /// Return exploration and content counts for the whole castle
+    pub fn castle_stats(&self) -> Stats {
+        self.dungeon.stats()
+    }
+
+    /// Return the number of monsters (and vendors) the player has killed
+    pub fn monsters_slain(&self) -> u32 {
+        self.monsters_slain
     }
 
     /// True if the player just bribed the monster in this room
@@ -1613,7 +3533,9 @@ impl Game {
     /// This is for debugging use only. The game automatically gives the orb of
     /// zot to the player at the appropriate time.
     pub fn debug_give_orb_of_zot(&mut self) {
-        self.player.give_orb_of_zot(true);
+        self.player
+            .give_orb_of_zot(true)
+            .expect("the Orb of Zot doesn't take a slot");
     }
 
     /// Give the player the runestaff
@@ -1621,7 +3543,18 @@ impl Game {
     /// This is for debugging use only. The game automatically gives the
     /// runestaff to the player at the appropriate time.
     pub fn debug_give_runestaff(&mut self) {
-        self.player.give_runestaff(true);
+        self.player
+            .give_runestaff(true)
+            .expect("the Runestaff doesn't take a slot");
+    }
+
+    /// Return a reference to the room at a location
+    ///
+    /// This is for tests only. Front-ends use `player_view()` so they only
+    /// show what the player knows.
+    #[cfg(test)]
+    pub(crate) fn debug_room_at(&self, x: u32, y: u32, z: u32) -> &Room {
+        self.dungeon.room_at(x, y, z)
     }
 
     /// Get the runestaff location
@@ -1642,3 +3575,888 @@ impl Game {
         format!("({},{},{})", loc.0 + 1, loc.1 + 1, loc.2 + 1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::PLAYER_CAPACITY;
+    use crate::ruleset::Ruleset;
+
+    /// Return a game with the player trading at a vendor
+    fn game_at_vendor() -> Game {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+
+        for z in 0..8 {
+            for y in 0..8 {
+                for x in 0..8 {
+                    if let RoomType::Monster(m) = game.debug_room_at(x, y, z).room_type() {
+                        if m.is_vendor() {
+                            game.player.set_position(x, y, z);
+                            game.state = GameState::Vendor;
+                            return game;
+                        }
+                    }
+                }
+            }
+        }
+
+        panic!("no vendor in the dungeon");
+    }
+
+    /// Return a game with the player facing a monster on a level, with empty
+    /// rooms all around
+    fn game_facing(monster_type: MonsterType, z: u32, config: GameConfig) -> Game {
+        let mut game = Game::new_with_config(8, 8, 8, config);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(2, 2, z);
+
+        for (x, y) in [(2, 1), (2, 3), (1, 2), (3, 2)] {
+            game.dungeon.room_at_mut(x, y, z).make_empty();
+        }
+
+        let monster = Monster::new(game.monster_def(monster_type), false);
+        game.dungeon.room_at_mut(2, 2, z).roomtype = RoomType::Monster(monster);
+        game.state = GameState::MonsterAttack;
+
+        game
+    }
+
+    /// Return the monster a pack call brought next to the player
+    fn called_monster(game: &Game) -> &Monster {
+        let z = *game.player.z();
+
+        [(2, 1), (2, 3), (1, 2), (3, 2)]
+            .iter()
+            .find_map(|&(x, y)| match game.dungeon.room_at(x, y, z).room_type() {
+                RoomType::Monster(m) => Some(m),
+                _ => None,
+            })
+            .expect("no monster answered the call")
+    }
+
+    #[test]
+    fn pack_calls_follow_the_spawn_mode() {
+        let config = GameConfig {
+            spawn_mode: SpawnMode::DepthScaled,
+            difficulty: Difficulty::Hard,
+            ..GameConfig::default()
+        };
+
+        let mut game = game_facing(MonsterType::Wolf, 7, config);
+        assert!(game.call_pack(MonsterType::Wolf));
+
+        let mut expected = Monster::new_at_depth(game.monster_def(MonsterType::Wolf), false, 7, 8);
+        expected.scale(Difficulty::Hard.monster_percent());
+
+        let called = called_monster(&game);
+        assert_eq!(called.hp(), expected.hp());
+        assert_eq!(called.damage(), expected.damage());
+        assert!(called.hp() > game.monster_def(MonsterType::Wolf).hp);
+
+        let mut game = game_facing(MonsterType::Wolf, 7, GameConfig::default());
+        assert!(game.call_pack(MonsterType::Wolf));
+        assert_eq!(
+            called_monster(&game).hp(),
+            game.monster_def(MonsterType::Wolf).hp
+        );
+    }
+
+    #[test]
+    fn pack_calls_need_an_empty_room() {
+        let mut game = game_facing(MonsterType::Wolf, 0, GameConfig::default());
+
+        for (x, y) in [(2, 1), (2, 3), (1, 2), (3, 2)] {
+            game.dungeon.room_at_mut(x, y, 0).roomtype = RoomType::Gold;
+        }
+
+        assert!(!game.call_pack(MonsterType::Wolf));
+    }
+
+    #[test]
+    fn wounded_trolls_regenerate() {
+        let mut game = game_facing(MonsterType::Troll, 0, GameConfig::default());
+
+        let monster = game.dungeon.monster_at_mut(2, 2, 0).unwrap();
+        monster.take_damage(3);
+
+        let (events, attacks) = game.monster_abilities();
+
+        assert!(matches!(
+            events.front(),
+            Some(CombatEvent::MonsterRegenerates(1..=2))
+        ));
+        assert_eq!(attacks, 1);
+    }
+
+    #[test]
+    fn chimeras_attack_twice() {
+        let mut game = game_facing(MonsterType::Chimera, 0, GameConfig::default());
+
+        let (events, attacks) = game.monster_abilities();
+
+        assert!(matches!(
+            events.back(),
+            Some(CombatEvent::MonsterMultiAttack(2))
+        ));
+        assert_eq!(attacks, 2);
+    }
+
+    #[test]
+    fn abilities_stay_quiet_without_the_rule() {
+        let config = GameConfig {
+            rules: Ruleset::classic(),
+            ..GameConfig::default()
+        };
+
+        let mut game = game_facing(MonsterType::Chimera, 0, config);
+        game.player.set_stat(Stat::Strength, 18);
+
+        // Without abilities the Chimera's one attack comes straight away
+        assert!(matches!(
+            game.be_attacked(),
+            Ok(CombatEvent::MonsterHit(..)
+                | CombatEvent::MonsterMiss
+                | CombatEvent::MonsterBlocked)
+        ));
+        assert_eq!(game.state, GameState::PlayerAttack);
+    }
+
+    #[test]
+    fn webs_hold_and_wounds_heal_between_fights() {
+        let config = GameConfig {
+            regeneration: true,
+            ..GameConfig::default()
+        };
+
+        let mut game = game_facing(MonsterType::Bear, 0, config);
+        game.state = GameState::Move;
+
+        let monster = game.dungeon.monster_at_mut(2, 2, 0).unwrap();
+        monster.set_webbed(3);
+        monster.take_damage(5);
+
+        game.add_turn(REGEN_TURNS * 2);
+
+        let monster = game.dungeon.monster_at_mut(2, 2, 0).unwrap();
+        assert_eq!(*monster.webbed(), 3);
+        assert_eq!(monster.hp(), monster.max_hp() - 3);
+    }
+
+    #[test]
+    fn exploring_stops_where_there_is_something_to_do() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(0, 1, 0);
+
+        let wolf = Monster::new(game.monster_def(MonsterType::Wolf), false);
+
+        let rooms = [
+            (RoomType::Empty, false),
+            (RoomType::Gold, false),
+            (RoomType::StairsDown, false),
+            (RoomType::Pool, true),
+            (RoomType::Chest, true),
+            (RoomType::Book, true),
+            (RoomType::CrystalOrb, true),
+            (RoomType::Monster(wolf), true),
+        ];
+
+        for (room_type, stop) in rooms {
+            game.dungeon.room_at_mut(0, 1, 0).roomtype = room_type;
+
+            assert_eq!(game.player_view().here_needs_attention(), stop);
+        }
+    }
+
+    #[test]
+    fn exploring_needs_sight_and_somewhere_new() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+
+        assert!(game.path_to_unexplored().is_ok());
+
+        game.player.set_blind(true);
+        assert_eq!(game.path_to_unexplored(), Err(Error::Blind));
+        game.player.set_blind(false);
+
+        for z in 0..8 {
+            for y in 0..8 {
+                for x in 0..8 {
+                    game.see_room(x, y, z);
+                }
+            }
+        }
+
+        assert_eq!(game.path_to_unexplored(), Err(Error::NoPath));
+    }
+
+    #[test]
+    fn combat_spells_need_the_wits_and_the_moment() {
+        let mut game = game_facing(MonsterType::Kobold, 0, GameConfig::default());
+        game.state = GameState::PlayerAttack;
+        game.spell_possible = true;
+
+        game.player.set_stat(Stat::Intelligence, 8);
+        assert_eq!(
+            game.cast_in_combat(SpellType::Fireball).err(),
+            Some(Error::SpellNotAvailable)
+        );
+
+        game.player.set_stat(Stat::Intelligence, 18);
+        assert_eq!(
+            game.cast_in_combat(SpellType::Light).err(),
+            Some(Error::SpellNotAvailable)
+        );
+        assert!(game.cast_in_combat(SpellType::Fireball).is_ok());
+
+        game.state = GameState::Move;
+        assert_eq!(
+            game.cast_in_combat(SpellType::Fireball).err(),
+            Some(Error::WrongState)
+        );
+    }
+
+    #[test]
+    fn bribe_chance_weighs_the_offer_against_the_ask() {
+        let mut game = game_facing(MonsterType::Kobold, 0, GameConfig::default());
+
+        // A Kobold has 3 hitpoints and does 1 damage
+        let price = 4 * game.config.economy.get(Price::Bribe);
+        assert_eq!(game.bribe_price(), price);
+
+        assert_eq!(game.bribe_chance(price), 50);
+        assert_eq!(game.bribe_chance(price / 2), 25);
+        assert_eq!(game.bribe_chance(0), 5);
+        assert_eq!(game.bribe_chance(price * 10), 95);
+
+        // Wounded monsters settle for less
+        game.dungeon.monster_at_mut(2, 2, 0).unwrap().take_damage(2);
+        assert_eq!(
+            game.bribe_price(),
+            2 * game.config.economy.get(Price::Bribe)
+        );
+        assert_eq!(game.bribe_chance(price), 95);
+    }
+
+    #[test]
+    fn bribes_need_something_to_give() {
+        let mut game = game_facing(MonsterType::Kobold, 0, GameConfig::default());
+        game.state = GameState::Move;
+        assert!(matches!(
+            game.room_effect(),
+            Event::Combat(MonsterType::Kobold)
+        ));
+
+        let gp = *game.player.gp();
+
+        assert_eq!(
+            game.bribe_offer(Bribe::Treasure(TreasureType::Palantir)),
+            Err(Error::NotCarrying)
+        );
+        assert_eq!(
+            game.bribe_offer(Bribe::Gold(gp + 1)),
+            Err(Error::NotEnoughGP)
+        );
+        assert_eq!(game.state, GameState::PlayerAttack);
+    }
+
+    #[test]
+    fn balrogs_refuse_bribes_unless_classic() {
+        let mut game = game_facing(MonsterType::Balrog, 0, GameConfig::default());
+        game.state = GameState::Move;
+        game.room_effect();
+
+        assert!(!game.bribe_possible());
+        assert_eq!(
+            game.bribe_offer(Bribe::Gold(0)),
+            Err(Error::BribeNotPossible)
+        );
+
+        let config = GameConfig {
+            rules: Ruleset::classic(),
+            ..GameConfig::default()
+        };
+
+        let mut game = game_facing(MonsterType::Balrog, 0, config);
+        game.state = GameState::Move;
+        game.room_effect();
+
+        assert!(game.bribe_possible());
+    }
+
+    /// Return a game at the entrance with a turn limit
+    fn game_with_turn_limit(limit: u32) -> Game {
+        let config = GameConfig {
+            turn_limit: Some(limit),
+            ..GameConfig::default()
+        };
+
+        let mut game = Game::new_with_config(8, 8, 8, config);
+
+        game.player_init(Race::Elf);
+        game.state = GameState::Move;
+
+        game
+    }
+
+    #[test]
+    fn turn_limit_ends_the_game_after_the_last_turn() {
+        let mut game = game_with_turn_limit(5);
+
+        game.add_turn(5);
+        assert_eq!(game.state(), GameState::Move);
+
+        game.add_turn(1);
+        assert_eq!(game.state(), GameState::OutOfTime);
+
+        // Without a limit the game goes on
+        let mut game = Game::new(8, 8, 8);
+        game.state = GameState::Move;
+        game.add_turn(1000);
+        assert_eq!(game.state(), GameState::Move);
+    }
+
+    #[test]
+    fn lethargy_spends_the_turn_limit_faster() {
+        let mut game = game_with_turn_limit(5);
+
+        let def = game.config.curses.get(CurseType::Lethargy).unwrap().clone();
+        game.player.add_curse(&def);
+
+        // The last turn counts twice, which is one too many
+        game.add_turn(5);
+        assert_eq!(game.state(), GameState::Move);
+
+        game.curse_effects();
+        assert_eq!(game.state(), GameState::OutOfTime);
+    }
+
+    #[test]
+    fn running_out_of_time_sticks() {
+        let mut game = game_with_turn_limit(5);
+
+        game.player.add_gp(10);
+        game.stash_deposit_gp(10).unwrap();
+
+        // Like a chest of gas just before the player walks out the door
+        game.add_turn(20);
+        game.move_dir(Direction::North);
+
+        assert_eq!(game.state(), GameState::OutOfTime);
+        assert_eq!(game.final_gp(), *game.player.gp());
+    }
+
+    #[test]
+    fn monsters_only_wander_into_empty_rooms_on_the_players_level() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(0, 0, 1);
+
+        for z in 1..=2 {
+            for y in 0..8 {
+                for x in 0..8 {
+                    game.dungeon.room_at_mut(x, y, z).roomtype = RoomType::Gold;
+                }
+            }
+        }
+
+        game.dungeon.room_at_mut(0, 0, 1).make_empty();
+        game.dungeon.room_at_mut(3, 4, 1).make_empty();
+
+        // One monster with a single way out, and one on another level with
+        // nothing but empty rooms around it
+        let orc = Monster::new(game.monster_def(MonsterType::Orc), false);
+        game.dungeon.room_at_mut(3, 3, 1).roomtype = RoomType::Monster(orc.clone());
+
+        game.dungeon.room_at_mut(3, 3, 2).roomtype = RoomType::Monster(orc);
+
+        for (x, y) in [(3, 2), (3, 4), (2, 3), (4, 3)] {
+            game.dungeon.room_at_mut(x, y, 2).make_empty();
+        }
+
+        let mut moved = false;
+
+        for _ in 0..1000 {
+            game.wander_monsters();
+
+            let at = |x, y| {
+                matches!(
+                    game.dungeon.room_at(x, y, 1).room_type(),
+                    RoomType::Monster(_)
+                )
+            };
+
+            assert!(at(3, 3) != at(3, 4));
+            moved |= at(3, 4);
+
+            let gold = (0..64)
+                .filter(|i| *game.dungeon.room_at(i % 8, i / 8, 1).room_type() == RoomType::Gold)
+                .count();
+            assert_eq!(gold, 61);
+
+            assert!(matches!(
+                game.dungeon.room_at(3, 3, 2).room_type(),
+                RoomType::Monster(_)
+            ));
+        }
+
+        assert!(moved);
+    }
+
+    #[test]
+    fn webbed_monsters_and_vendors_stay_put() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(0, 0, 1);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                game.dungeon.room_at_mut(x, y, 1).make_empty();
+            }
+        }
+
+        let mut orc = Monster::new(game.monster_def(MonsterType::Orc), false);
+        orc.set_webbed(5);
+        game.dungeon.room_at_mut(3, 3, 1).roomtype = RoomType::Monster(orc);
+
+        let vendor = Monster::new(game.config.monsters.vendor().unwrap(), false);
+        game.dungeon.room_at_mut(5, 5, 1).roomtype = RoomType::Monster(vendor);
+
+        for _ in 0..500 {
+            game.wander_monsters();
+        }
+
+        for (x, y) in [(3, 3), (5, 5)] {
+            assert!(matches!(
+                game.dungeon.room_at(x, y, 1).room_type(),
+                RoomType::Monster(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn news_reaches_this_level_first_and_others_at_half_strength() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+
+        let here = (1, 1, 3);
+        let same_level = (5, 5, 3);
+        let other_level = (5, 5, 4);
+
+        for (x, y, z) in [here, same_level, other_level] {
+            let vendor = Monster::new(game.config.monsters.vendor().unwrap(), false);
+            game.dungeon.room_at_mut(x, y, z).roomtype = RoomType::Monster(vendor);
+        }
+
+        game.player.set_position(here.0, here.1, here.2);
+        game.spread_word(-MURDER_NEWS);
+
+        let reputation = |game: &Game, (x, y, z): (u32, u32, u32)| match game
+            .dungeon
+            .room_at(x, y, z)
+            .room_type()
+        {
+            RoomType::Monster(m) => m.reputation(),
+            _ => panic!("the vendor is gone"),
+        };
+
+        for _ in 1..NEWS_LEVEL_TURNS {
+            game.spread_news();
+        }
+
+        assert_eq!(reputation(&game, same_level), 0);
+
+        game.spread_news();
+
+        assert_eq!(reputation(&game, same_level), -MURDER_NEWS);
+        assert_eq!(reputation(&game, other_level), 0);
+
+        for _ in NEWS_LEVEL_TURNS..NEWS_CASTLE_TURNS - 1 {
+            game.spread_news();
+        }
+
+        assert_eq!(reputation(&game, other_level), 0);
+
+        game.spread_news();
+
+        assert_eq!(reputation(&game, same_level), -MURDER_NEWS);
+        assert_eq!(reputation(&game, other_level), -MURDER_NEWS / 2);
+        assert!(game.news.is_empty());
+
+        // The vendor it happened to doesn't hear about it secondhand
+        assert_eq!(reputation(&game, here), 0);
+    }
+
+    /// Return a game at the entrance with 10 GP and a Palantir in the stash,
+    /// and 5 GP and a Ruby Red in hand
+    fn game_with_stash() -> Game {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+        game.state = GameState::Move;
+        game.player.spend(*game.player.gp()).unwrap();
+        game.player.add_gp(15);
+
+        assert!(game.give_item(Item::Treasure(TreasureType::Palantir), 1));
+        assert!(game.give_item(Item::Treasure(TreasureType::RubyRed), 1));
+
+        game.stash_deposit_gp(10).unwrap();
+        game.stash_deposit_treasure(TreasureType::Palantir).unwrap();
+
+        game
+    }
+
+    #[test]
+    fn stash_counts_only_when_the_player_walks_out() {
+        let mut game = game_with_stash();
+
+        assert_eq!(game.final_gp(), 5);
+        assert_eq!(game.final_treasures(), vec![TreasureType::RubyRed]);
+
+        game.move_dir(Direction::North);
+        assert_eq!(game.state(), GameState::Exit);
+
+        assert_eq!(game.final_gp(), 15);
+        assert_eq!(
+            game.final_treasures(),
+            vec![TreasureType::RubyRed, TreasureType::Palantir]
+        );
+    }
+
+    #[test]
+    fn stash_is_lost_to_the_dead() {
+        let mut game = game_with_stash();
+
+        game.state = GameState::Dead;
+
+        assert_eq!(game.final_gp(), 5);
+        assert_eq!(game.final_treasures(), vec![TreasureType::RubyRed]);
+    }
+
+    #[test]
+    fn stash_is_only_at_the_entrance() {
+        let mut game = game_with_stash();
+
+        game.player.set_position(0, 1, 0);
+        game.dungeon.room_at_mut(0, 1, 0).make_empty();
+
+        assert_eq!(game.stash_withdraw_gp(10), Err(Error::NotAtEntrance));
+        assert_eq!(
+            game.stash_withdraw_treasure(TreasureType::Palantir),
+            Err(Error::NotAtEntrance)
+        );
+        assert_eq!(game.stash_gp(), 10);
+    }
+
+    /// Return a game with a vendor offering 1 GP for the player's Palantir
+    fn game_haggling() -> Game {
+        let mut game = game_at_vendor();
+
+        assert!(game.give_item(Item::Treasure(TreasureType::Palantir), 1));
+        assert!(game.vendor_treasure_offer().is_ok());
+
+        game.set_vendor_price(TreasureType::Palantir, 1);
+        game.vendor_mood = 1;
+
+        game
+    }
+
+    #[test]
+    fn haggling_rejects_asking_nothing() {
+        let mut game = game_haggling();
+
+        assert!(matches!(
+            game.vendor_treasure_counter(TreasureType::Palantir, 0),
+            Err(Error::BadAmount)
+        ));
+        assert!(game.player.has_treasure(TreasureType::Palantir));
+    }
+
+    #[test]
+    fn haggling_takes_an_ask_at_or_below_the_offer() {
+        let mut game = game_haggling();
+        let gp = *game.player.gp();
+
+        assert!(matches!(
+            game.vendor_treasure_counter(TreasureType::Palantir, 1),
+            Ok(HaggleEvent::Accepted(1))
+        ));
+        assert!(!game.player.has_treasure(TreasureType::Palantir));
+        assert_eq!(*game.player.gp(), gp + 1);
+    }
+
+    #[test]
+    fn haggling_refuses_more_than_the_treasure_is_worth() {
+        let mut game = game_haggling();
+
+        assert!(matches!(
+            game.vendor_treasure_counter(TreasureType::Palantir, u32::MAX),
+            Ok(HaggleEvent::Refused)
+        ));
+        assert!(game.player.has_treasure(TreasureType::Palantir));
+        assert_eq!(game.state, GameState::Vendor);
+    }
+
+    #[test]
+    fn pushy_hagglers_drive_the_vendor_away() {
+        let mut game = game_haggling();
+
+        game.vendor_pushes = 20;
+
+        let ask = game
+            .treasure_max_value(TreasureType::Palantir)
+            .min(game.vendor_purse());
+        assert!(ask > 1);
+
+        assert!(matches!(
+            game.vendor_treasure_counter(TreasureType::Palantir, ask),
+            Ok(HaggleEvent::WalkedAway)
+        ));
+        assert!(game.player.has_treasure(TreasureType::Palantir));
+        assert_eq!(game.state, GameState::Move);
+    }
+
+    #[test]
+    fn full_pack_leaves_items_on_the_floor() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+
+        let mut pack = Inventory::new(1);
+        pack.add(Item::Lamp, 1).unwrap();
+        *game.player.inventory_mut() = pack;
+
+        assert!(!game.give_item(Item::Flare, 3));
+        assert_eq!(
+            game.room_items(),
+            vec![Stack {
+                item: Item::Flare,
+                count: 3
+            }]
+        );
+
+        // What the player came for always fits
+        assert!(game.give_item(Item::Treasure(TreasureType::Palantir), 1));
+        assert!(game.give_item(Item::Runestaff, 1));
+        assert_eq!(game.player.give_orb_of_zot(true), Ok(()));
+        assert_eq!(game.player.set_lamp(true), Ok(()));
+    }
+
+    #[test]
+    fn classic_pack_has_no_limit() {
+        let config = GameConfig {
+            rules: Ruleset::classic(),
+            ..GameConfig::default()
+        };
+
+        let mut game = Game::new_with_config(8, 8, 8, config);
+
+        game.player_init(Race::Elf);
+
+        assert_eq!(game.player_inventory_capacity(), None);
+        assert!(game.give_item(Item::Flare, 3));
+        assert!(game.room_items().is_empty());
+
+        let game = Game::new(8, 8, 8);
+
+        assert_eq!(game.player_inventory_capacity(), Some(PLAYER_CAPACITY));
+    }
+
+    /// Return a game with the player standing in a room with a curse
+    fn game_in_cursed_room(rules: Ruleset) -> Game {
+        let config = GameConfig {
+            rules,
+            ..GameConfig::default()
+        };
+
+        let mut game = Game::new_with_config(8, 8, 8, config);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(0, 1, 0);
+        game.dungeon
+            .room_at_mut(0, 1, 0)
+            .set_curse(CurseType::Lethargy);
+
+        game
+    }
+
+    #[test]
+    fn caught_curses_leave_rooms_under_the_rule() {
+        let mut game = game_in_cursed_room(Ruleset::modern());
+
+        assert_eq!(game.curse_check(), Some(CurseType::Lethargy));
+        assert_eq!(*game.room_at_player().curse(), CurseType::None);
+        assert_eq!(game.curse_check(), None);
+        assert_eq!(game.player.curse_stacks(CurseType::Lethargy), 1);
+    }
+
+    #[test]
+    fn classic_curses_stay_in_rooms() {
+        let mut game = game_in_cursed_room(Ruleset::classic());
+
+        assert_eq!(game.curse_check(), Some(CurseType::Lethargy));
+        assert_eq!(*game.room_at_player().curse(), CurseType::Lethargy);
+
+        // Coming back doesn't pile the curse on again
+        assert_eq!(game.curse_check(), None);
+        assert_eq!(game.player.curse_stacks(CurseType::Lethargy), 1);
+    }
+
+    #[test]
+    fn only_dealing_with_a_room_clears_it() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(0, 1, 0);
+
+        let cleared = game.level_stats(0).cleared;
+
+        game.dungeon.room_at_mut(0, 1, 0).roomtype = RoomType::Gold;
+        game.room_effect_gold();
+        assert_eq!(game.level_stats(0).cleared, cleared);
+
+        game.dungeon.room_at_mut(0, 1, 0).roomtype = RoomType::Book;
+        assert!(game.open_book().is_ok());
+        assert_eq!(game.level_stats(0).cleared, cleared + 1);
+
+        // Clearing the same room again doesn't count twice
+        game.dungeon.room_at_mut(0, 1, 0).roomtype = RoomType::Book;
+        assert!(game.open_book().is_ok());
+        assert_eq!(game.level_stats(0).cleared, cleared + 1);
+        assert_eq!(game.castle_stats().cleared, cleared + 1);
+    }
+
+    /// Return a game with the player standing at a chest that will hold
+    /// whatever chest fate 4 gives
+    fn game_at_chest(rules: Ruleset) -> Game {
+        let config = GameConfig {
+            rules,
+            ..GameConfig::default()
+        };
+
+        let mut game = Game::new_with_config(8, 8, 8, config);
+
+        game.player_init(Race::Elf);
+        game.player.set_position(0, 1, 0);
+        game.dungeon.room_at_mut(0, 1, 0).roomtype = RoomType::Chest;
+        game.fates.insert(Fate::Chest, 4);
+
+        game
+    }
+
+    #[test]
+    fn classic_chests_hold_gold_not_gear() {
+        let mut game = game_at_chest(Ruleset::classic());
+
+        assert!(matches!(game.open_chest(), Ok(ChestEvent::Treasure(_))));
+        assert_eq!(game.take_found_equipment(), Err(Error::WrongState));
+
+        let mut game = game_at_chest(Ruleset::modern());
+
+        assert!(matches!(game.open_chest(), Ok(ChestEvent::Equipment(_))));
+    }
+
+    #[test]
+    fn declined_gear_is_gone_after_moving_on() {
+        let mut game = game_at_chest(Ruleset::modern());
+
+        game.open_chest().unwrap();
+        game.decline_found_equipment();
+        game.move_dir(Direction::South);
+
+        assert_eq!(game.take_found_equipment(), Err(Error::WrongState));
+
+        // Walking off without answering leaves it behind too
+        let mut game = game_at_chest(Ruleset::modern());
+
+        game.open_chest().unwrap();
+        game.move_dir(Direction::South);
+
+        assert_eq!(game.take_found_equipment(), Err(Error::WrongState));
+
+        // But it's there for the taking before moving on
+        let mut game = game_at_chest(Ruleset::modern());
+
+        let Ok(ChestEvent::Equipment(gear)) = game.open_chest() else {
+            panic!("chest didn't hold gear");
+        };
+
+        assert_eq!(game.take_found_equipment(), Ok(gear));
+    }
+
+    #[test]
+    fn weapon_wears_only_under_the_rule() {
+        let config = GameConfig {
+            wear_chance: 1,
+            ..GameConfig::default()
+        };
+
+        let mut game = Game::new_with_config(8, 8, 8, config);
+
+        game.player_init(Race::Elf);
+        game.player.set_weapon(Weapon::new(WeaponType::Dagger));
+
+        // A dagger takes seven hits
+        for _ in 0..6 {
+            assert!(game.hit_wears_weapon());
+            assert!(!game.player.wear_weapon(1));
+        }
+
+        assert!(game.player.wear_weapon(1));
+        assert_eq!(game.player.weapon().weapon_type(), WeaponType::None);
+
+        game.config.rules = Ruleset::classic();
+
+        assert!(!game.hit_wears_weapon());
+    }
+
+    #[test]
+    fn path_to_unknown_room_fails() {
+        let mut game = Game::new(8, 8, 8);
+
+        game.player_init(Race::Elf);
+
+        assert_eq!(game.path_to(4, 4, 7), Err(Error::NoPath));
+        assert_eq!(game.path_to(8, 0, 0), Err(Error::OutOfBounds));
+        assert_eq!(
+            game.path_to(game.player_x(), game.player_y(), 0),
+            Ok(Vec::new())
+        );
+    }
+
+    #[test]
+    fn vendor_pays_for_second_treasure_from_what_is_left() {
+        let mut game = game_at_vendor();
+        let treasures = [TreasureType::RubyRed, TreasureType::NornStone];
+
+        for t in treasures {
+            game.player
+                .inventory_mut()
+                .add(Item::Treasure(t), 1)
+                .unwrap();
+        }
+
+        let purse = game.vendor_purse();
+        assert!(game.vendor_stock_mut().unwrap().pay(purse - 1000));
+
+        game.vendor_treasure_offer().unwrap();
+
+        for t in treasures {
+            game.set_vendor_price(t, 800);
+        }
+
+        game.vendor_treasure_accept(treasures[0]).unwrap();
+        assert_eq!(game.vendor_treasure_price(treasures[1]), 200);
+
+        game.vendor_treasure_accept(treasures[1]).unwrap();
+        assert_eq!(game.vendor_purse(), 0);
+    }
+}
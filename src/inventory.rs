@@ -0,0 +1,235 @@
+use crate::error::Error;
+use crate::treasure::TreasureType;
+
+/// Number of different things the player can carry, not counting treasures,
+/// the Runestaff, and the Orb of Zot
+pub const PLAYER_CAPACITY: usize = 10;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Item {
+    Lamp,
+    Flare,
+    Treasure(TreasureType),
+    OrbOfZot,
+    Runestaff,
+    HealingPotion,
+    SightElixir, // Cures blindness
+    MappingScroll,
+    Solvent, // Frees a book stuck to the hands
+}
+
+impl Item {
+    /// Return the most of this item that fits in one stack
+    pub fn max_stack(&self) -> u32 {
+        match self {
+            Item::Flare
+            | Item::HealingPotion
+            | Item::SightElixir
+            | Item::MappingScroll
+            | Item::Solvent => u32::MAX,
+            _ => 1,
+        }
+    }
+
+    /// True if the item takes up one of the player's slots
+    ///
+    /// Treasures, the Runestaff, and the Orb of Zot are what the player came
+    /// for, so a full pack never keeps them from being picked up.
+    pub fn takes_slot(&self) -> bool {
+        !matches!(self, Item::Treasure(_) | Item::Runestaff | Item::OrbOfZot)
+    }
+
+    /// True if the item is used up with the USE command
+    pub fn is_usable(&self) -> bool {
+        matches!(
+            self,
+            Item::HealingPotion | Item::SightElixir | Item::MappingScroll | Item::Solvent
+        )
+    }
+}
+
+/// Some number of one kind of item
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Stack {
+    pub item: Item,
+    pub count: u32,
+}
+
+/// Items carried by the player or lying in a room
+///
+/// Each kind of item takes up one slot no matter how many there are, except
+/// for the ones that don't take a slot at all.
+#[derive(Debug)]
+pub struct Inventory {
+    stacks: Vec<Stack>,
+    capacity: usize,
+}
+
+impl Default for Inventory {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+impl Inventory {
+    /// Create an inventory holding at most `capacity` kinds of item
+    pub fn new(capacity: usize) -> Inventory {
+        Inventory {
+            stacks: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Create an inventory that holds any number of items
+    pub fn unlimited() -> Inventory {
+        Inventory::new(usize::MAX)
+    }
+
+    /// Return the stacks in the order they were added
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    /// Return the number of kinds of item this can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Return the number of slots in use
+    pub fn slots_used(&self) -> usize {
+        self.stacks.iter().filter(|s| s.item.takes_slot()).count()
+    }
+
+    /// True if there's nothing here
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Return how many of an item there are
+    pub fn count(&self, item: Item) -> u32 {
+        match self.stacks.iter().find(|s| s.item == item) {
+            Some(stack) => stack.count,
+            None => 0,
+        }
+    }
+
+    /// True if there's at least one of an item
+    pub fn has(&self, item: Item) -> bool {
+        self.count(item) > 0
+    }
+
+    /// True if there's room for some of an item
+    pub fn can_add(&self, item: Item) -> bool {
+        !item.takes_slot() || self.has(item) || self.slots_used() < self.capacity
+    }
+
+    /// Add some of an item
+    ///
+    /// Stacks stop growing at the item's limit, so adding another lamp to a
+    /// lamp does nothing.
+    pub fn add(&mut self, item: Item, count: u32) -> Result<(), Error> {
+        if count == 0 {
+            return Ok(());
+        }
+
+        if !self.can_add(item) {
+            return Err(Error::InventoryFull);
+        }
+
+        match self.stacks.iter_mut().find(|s| s.item == item) {
+            Some(stack) => {
+                stack.count = stack.count.saturating_add(count).min(item.max_stack());
+            }
+            None => self.stacks.push(Stack {
+                item,
+                count: count.min(item.max_stack()),
+            }),
+        }
+
+        Ok(())
+    }
+
+    /// Remove up to `count` of an item
+    ///
+    /// Returns the number removed
+    pub fn remove(&mut self, item: Item, count: u32) -> u32 {
+        let i = match self.stacks.iter().position(|s| s.item == item) {
+            Some(i) => i,
+            None => return 0,
+        };
+
+        let removed = count.min(self.stacks[i].count);
+
+        self.stacks[i].count -= removed;
+
+        if self.stacks[i].count == 0 {
+            self.stacks.remove(i);
+        }
+
+        removed
+    }
+
+    /// Remove everything
+    pub fn clear(&mut self) {
+        self.stacks.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return a pack with every slot taken
+    fn full_pack() -> Inventory {
+        let mut pack = Inventory::new(3);
+
+        pack.add(Item::Lamp, 1).unwrap();
+        pack.add(Item::Flare, 5).unwrap();
+        pack.add(Item::HealingPotion, 1).unwrap();
+
+        pack
+    }
+
+    #[test]
+    fn full_pack_refuses_new_kinds() {
+        let mut pack = full_pack();
+
+        assert!(!pack.can_add(Item::Solvent));
+        assert_eq!(pack.add(Item::Solvent, 1), Err(Error::InventoryFull));
+        assert!(!pack.has(Item::Solvent));
+    }
+
+    #[test]
+    fn full_pack_still_stacks() {
+        let mut pack = full_pack();
+
+        assert_eq!(pack.add(Item::Flare, 5), Ok(()));
+        assert_eq!(pack.count(Item::Flare), 10);
+
+        // Only one lamp fits in its slot
+        assert_eq!(pack.add(Item::Lamp, 1), Ok(()));
+        assert_eq!(pack.count(Item::Lamp), 1);
+    }
+
+    #[test]
+    fn goal_items_never_need_a_slot() {
+        let mut pack = full_pack();
+
+        for t in [TreasureType::RubyRed, TreasureType::Silmaril] {
+            assert_eq!(pack.add(Item::Treasure(t), 1), Ok(()));
+        }
+
+        assert_eq!(pack.add(Item::Runestaff, 1), Ok(()));
+        assert_eq!(pack.add(Item::OrbOfZot, 1), Ok(()));
+        assert_eq!(pack.slots_used(), 3);
+        assert_eq!(pack.stacks().len(), 7);
+    }
+
+    #[test]
+    fn emptied_slot_frees_up() {
+        let mut pack = full_pack();
+
+        assert_eq!(pack.remove(Item::Flare, 9), 5);
+        assert_eq!(pack.add(Item::Solvent, 1), Ok(()));
+    }
+}
@@ -1,10 +1,25 @@
 pub mod armor;
+pub mod config;
 pub mod curse;
+pub mod data;
+pub mod difficulty;
 pub mod dungeon;
+pub mod economy;
+pub mod equipment;
 pub mod error;
 pub mod game;
+pub mod inventory;
 pub mod monster;
+pub mod path;
 pub mod player;
+pub mod ring;
 pub mod room;
+pub mod ruleset;
+pub mod shield;
+pub mod speedrun;
+pub mod spell;
+pub mod stats;
+pub mod stock;
 pub mod treasure;
+pub mod view;
 pub mod weapon;
@@ -1,6 +1,23 @@
-pub const MONSTER_COUNT: u32 = 13;
+use crate::data;
+use crate::economy::Economy;
+use crate::error::Error;
+use crate::stock::Stock;
 
+/// How far a vendor's opinion of the player can go either way
+pub const MAX_REPUTATION: i32 = 100;
+
+/// How monsters are chosen when the dungeon is built
 #[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SpawnMode {
+    /// Every level cycles through the same list, Kobold to Dragon
+    Classic,
+
+    /// Shallow levels favor weak monsters, deep levels favor strong ones, and
+    /// monster stats grow with depth
+    DepthScaled,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum MonsterType {
     Kobold,
     Orc,
@@ -15,57 +32,252 @@ pub enum MonsterType {
     Balrog,
     Dragon,
     Vendor,
+    Custom(u32), // Defined in a monster data file, numbered by position in the file
+}
+
+impl MonsterType {
+    /// Return the classic monster type with a given name
+    fn classic(name: &str) -> Option<MonsterType> {
+        match name.to_uppercase().as_str() {
+            "KOBOLD" => Some(MonsterType::Kobold),
+            "ORC" => Some(MonsterType::Orc),
+            "WOLF" => Some(MonsterType::Wolf),
+            "GOBLIN" => Some(MonsterType::Goblin),
+            "OGRE" => Some(MonsterType::Ogre),
+            "TROLL" => Some(MonsterType::Troll),
+            "BEAR" => Some(MonsterType::Bear),
+            "MINOTAUR" => Some(MonsterType::Minotaur),
+            "GARGOYLE" => Some(MonsterType::Gargoyle),
+            "CHIMERA" => Some(MonsterType::Chimera),
+            "BALROG" => Some(MonsterType::Balrog),
+            "DRAGON" => Some(MonsterType::Dragon),
+            "VENDOR" => Some(MonsterType::Vendor),
+            _ => None,
+        }
+    }
+}
+
+/// Special monster behaviors
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum MonsterFlag {
+    Vendor,
+    NoBribe,
+
+    // Combat abilities
+    Fire,       // Sometimes breathes fire that armor doesn't stop
+    Fear,       // Sometimes terrifies the player, costing intelligence
+    Regenerate, // Heals a little every round
+    Resist,     // Takes half damage from melee
+    Multi(u32), // Attacks this many times a round
+    Pack,       // Sometimes calls another of its kind into a nearby room
+}
+
+impl MonsterFlag {
+    fn from_name(name: &str) -> Option<MonsterFlag> {
+        let name = name.to_lowercase();
+
+        if let Some(count) = name.strip_prefix("multi:") {
+            return match count.parse() {
+                Ok(n) if n > 0 => Some(MonsterFlag::Multi(n)),
+                _ => None,
+            };
+        }
+
+        match name.as_str() {
+            "vendor" => Some(MonsterFlag::Vendor),
+            "nobribe" => Some(MonsterFlag::NoBribe),
+            "fire" => Some(MonsterFlag::Fire),
+            "fear" => Some(MonsterFlag::Fear),
+            "regen" => Some(MonsterFlag::Regenerate),
+            "resist" => Some(MonsterFlag::Resist),
+            "pack" => Some(MonsterFlag::Pack),
+            _ => None,
+        }
+    }
+}
+
+/// Everything needed to make a kind of monster
+#[derive(Debug, Clone)]
+pub struct MonsterDef {
+    pub monster_type: MonsterType,
+    pub name: String,
+    pub article: String,
+    pub hp: u32,
+    pub damage: u32,
+    pub break_chance: u32, // 1 in this many melee hits breaks a weapon, 0 for never
+    pub flags: Vec<MonsterFlag>,
+    pub spawn_weight: u32,
+}
+
+impl MonsterDef {
+    /// True if the monster has a flag
+    pub fn has_flag(&self, flag: MonsterFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+}
+
+/// All the kinds of monster in a game
+#[derive(Debug, Clone)]
+pub struct MonsterTable {
+    defs: Vec<MonsterDef>,
+}
+
+impl MonsterTable {
+    /// Return the classic monsters
+    pub fn classic() -> MonsterTable {
+        MonsterTable::parse(include_str!("../data/monsters.txt"))
+            .expect("built-in monster data is broken")
+    }
+
+    /// Load monsters from a data file
+    pub fn load(path: &str) -> Result<MonsterTable, Error> {
+        MonsterTable::parse(&data::read_file(path)?)
+    }
+
+    /// Parse monster data
+    ///
+    /// See `data/monsters.txt` for the format. There has to be at least one
+    /// monster that can be placed and one vendor.
+    pub fn parse(text: &str) -> Result<MonsterTable, Error> {
+        let mut defs: Vec<MonsterDef> = Vec::new();
+
+        for (i, record) in data::records(text).iter().enumerate() {
+            let bad = Error::BadData(record.line);
+
+            if record.fields.len() != 7 {
+                return Err(bad);
+            }
+
+            let name = record.str(0)?.replace('_', " ");
+
+            let monster_type = MonsterType::classic(&name).unwrap_or(MonsterType::Custom(i as u32));
+
+            if defs.iter().any(|d| d.monster_type == monster_type) {
+                return Err(bad);
+            }
+
+            let mut flags = Vec::new();
+
+            for f in record.list(6)? {
+                flags.push(MonsterFlag::from_name(f).ok_or(bad)?);
+            }
+
+            defs.push(MonsterDef {
+                monster_type,
+                name,
+                article: String::from(record.str(1)?),
+                hp: record.u32(2)?,
+                damage: record.u32(3)?,
+                break_chance: record.u32(4)?,
+                flags,
+                spawn_weight: record.u32(5)?,
+            });
+        }
+
+        let table = MonsterTable { defs };
+
+        if table.spawnable().is_empty() || table.vendor().is_none() {
+            return Err(Error::DataFile);
+        }
+
+        Ok(table)
+    }
+
+    /// Return the definition for a monster type
+    pub fn get(&self, monster_type: MonsterType) -> Option<&MonsterDef> {
+        self.defs.iter().find(|d| d.monster_type == monster_type)
+    }
+
+    /// Return the monsters that can be placed in the castle, weakest first
+    pub fn spawnable(&self) -> Vec<&MonsterDef> {
+        self.defs
+            .iter()
+            .filter(|d| d.spawn_weight > 0 && !d.has_flag(MonsterFlag::Vendor))
+            .collect()
+    }
+
+    /// Return the definition used for vendors
+    pub fn vendor(&self) -> Option<&MonsterDef> {
+        self.defs.iter().find(|d| d.has_flag(MonsterFlag::Vendor))
+    }
+}
+
+impl Default for MonsterTable {
+    fn default() -> MonsterTable {
+        MonsterTable::classic()
+    }
+}
+
+/// How badly hurt a monster is
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum WoundLevel {
+    Unhurt,
+    Scratched,    // Down to three quarters of its hitpoints
+    Wounded,      // Down to half
+    BadlyWounded, // Down to a quarter
+    NearDeath,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct Monster {
     monster_type: MonsterType,
     hp: u32,
+    max_hp: u32,
     damage: u32,
-    break_weapon: bool,
+    break_chance: u32,
+    flags: Vec<MonsterFlag>,
     has_runestaff: bool,
-    webbed: u32, // How many turns left stuck in a web
+    webbed: u32,          // How many turns left stuck in a web
+    stock: Option<Stock>, // What a vendor has to sell
+    reputation: i32,      // What a vendor thinks of the player, 0 for neutral
 }
 
 impl Monster {
-    pub fn new(monster_type: MonsterType, has_runestaff: bool) -> Monster {
-        let monster_num = Monster::get_monster_num(monster_type);
-
-        let m1 = monster_num + 1; // Change to 1-based
-
-        let hp = m1 + 2;
-        let damage = 1 + m1 / 2;
-
-        let break_weapon =
-            monster_type == MonsterType::Gargoyle || monster_type == MonsterType::Dragon;
-
+    pub fn new(def: &MonsterDef, has_runestaff: bool) -> Monster {
         Monster {
-            monster_type,
-            hp,
-            damage,
-            break_weapon,
+            monster_type: def.monster_type,
+            hp: def.hp,
+            max_hp: def.hp,
+            damage: def.damage,
+            break_chance: def.break_chance,
+            flags: def.flags.clone(),
             has_runestaff,
             webbed: 0,
+            stock: None,
+            reputation: 0,
         }
     }
 
-    /// Return a MonsterType for a given ID
-    fn get_monster_num(monster_type: MonsterType) -> u32 {
-        match monster_type {
-            MonsterType::Kobold => 0,
-            MonsterType::Orc => 1,
-            MonsterType::Wolf => 2,
-            MonsterType::Goblin => 3,
-            MonsterType::Ogre => 4,
-            MonsterType::Troll => 5,
-            MonsterType::Bear => 6,
-            MonsterType::Minotaur => 7,
-            MonsterType::Gargoyle => 8,
-            MonsterType::Chimera => 9,
-            MonsterType::Balrog => 10,
-            MonsterType::Dragon => 11,
-            MonsterType::Vendor => 12,
-        }
+    /// Create a new vendor with goods for the level it lives on
+    pub fn new_vendor(def: &MonsterDef, z: u32, zsize: u32, economy: &Economy) -> Monster {
+        let mut monster = Monster::new(def, false);
+
+        monster.stock = Some(Stock::new(z, zsize, economy));
+
+        monster
+    }
+
+    /// Create a new monster with stats scaled for the level it lives on
+    ///
+    /// Monsters on the top level are unchanged, and those on the bottom level
+    /// get half again their usual hit points and damage.
+    pub fn new_at_depth(def: &MonsterDef, has_runestaff: bool, z: u32, zsize: u32) -> Monster {
+        let mut monster = Monster::new(def, has_runestaff);
+
+        let max_z = std::cmp::max(zsize, 2) - 1;
+
+        monster.hp += monster.hp * z / (2 * max_z);
+        monster.max_hp = monster.hp;
+        monster.damage += monster.damage * z / (2 * max_z);
+
+        monster
+    }
+
+    /// Scale the monster's hit points and damage by a percent
+    pub fn scale(&mut self, percent: u32) {
+        self.hp = std::cmp::max(1, self.hp * percent / 100);
+        self.max_hp = self.hp;
+        self.damage = std::cmp::max(1, self.damage * percent / 100);
     }
 
     /// Return the monster's type
@@ -73,9 +285,60 @@ impl Monster {
         self.monster_type
     }
 
-    /// Return true if the monster can break a weapon
-    pub fn can_break_weapon(&self) -> bool {
-        self.break_weapon
+    /// Return the 1 in N chance of breaking a weapon, 0 for never
+    pub fn break_chance(&self) -> u32 {
+        self.break_chance
+    }
+
+    /// True if the monster has a flag
+    pub fn has_flag(&self, flag: MonsterFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    /// Return the number of attacks the monster gets each round
+    pub fn attacks(&self) -> u32 {
+        self.flags
+            .iter()
+            .find_map(|f| match f {
+                MonsterFlag::Multi(n) => Some(*n),
+                _ => None,
+            })
+            .unwrap_or(1)
+    }
+
+    /// True if the monster is a vendor
+    pub fn is_vendor(&self) -> bool {
+        self.has_flag(MonsterFlag::Vendor)
+    }
+
+    /// Return what a vendor has to sell
+    pub fn stock(&self) -> Option<&Stock> {
+        self.stock.as_ref()
+    }
+
+    /// Return what a vendor has to sell, mutably
+    pub fn stock_mut(&mut self) -> Option<&mut Stock> {
+        self.stock.as_mut()
+    }
+
+    /// Take everything the vendor was carrying
+    pub fn take_stock(&mut self) -> Option<Stock> {
+        self.stock.take()
+    }
+
+    /// Return what the monster thinks of the player
+    pub fn reputation(&self) -> i32 {
+        self.reputation
+    }
+
+    /// Change what the monster thinks of the player
+    pub fn change_reputation(&mut self, delta: i32) {
+        self.set_reputation(self.reputation + delta);
+    }
+
+    /// Set what the monster thinks of the player
+    pub fn set_reputation(&mut self, reputation: i32) {
+        self.reputation = reputation.clamp(-MAX_REPUTATION, MAX_REPUTATION);
     }
 
     /// Damage the monster
@@ -127,4 +390,124 @@ impl Monster {
     pub fn hp(&self) -> u32 {
         self.hp
     }
+
+    /// Return hitpoints when unhurt
+    pub fn max_hp(&self) -> u32 {
+        self.max_hp
+    }
+
+    /// Return how badly hurt the monster is
+    pub fn wound_level(&self) -> WoundLevel {
+        if self.hp >= self.max_hp {
+            WoundLevel::Unhurt
+        } else if self.hp * 4 >= self.max_hp * 3 {
+            WoundLevel::Scratched
+        } else if self.hp * 2 >= self.max_hp {
+            WoundLevel::Wounded
+        } else if self.hp * 4 >= self.max_hp {
+            WoundLevel::BadlyWounded
+        } else {
+            WoundLevel::NearDeath
+        }
+    }
+
+    /// Heal the monster, up to its maximum hitpoints
+    ///
+    /// Return the number of hitpoints actually healed
+    pub fn heal(&mut self, amount: u32) -> u32 {
+        let healed = std::cmp::min(amount, self.max_hp - self.hp);
+
+        self.hp += healed;
+
+        healed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VENDOR: &str = "Vendor A 15 7 0 0 vendor\n";
+
+    #[test]
+    fn classic_data_matches_original_stats() {
+        let table = MonsterTable::classic();
+        let spawnable = table.spawnable();
+
+        assert_eq!(spawnable.len(), 12);
+
+        // The original worked out stats from each monster's place in the list
+        for (n, def) in spawnable.iter().enumerate() {
+            let n = n as u32 + 1;
+
+            assert_eq!(def.hp, n + 2, "{}", def.name);
+            assert_eq!(def.damage, 1 + n / 2, "{}", def.name);
+        }
+
+        assert_eq!(spawnable[0].monster_type, MonsterType::Kobold);
+        assert_eq!(spawnable[11].monster_type, MonsterType::Dragon);
+
+        for def in spawnable {
+            let breaks = matches!(
+                def.monster_type,
+                MonsterType::Gargoyle | MonsterType::Dragon
+            );
+
+            assert_eq!(def.break_chance > 0, breaks, "{}", def.name);
+        }
+
+        let vendor = table.vendor().unwrap();
+
+        assert_eq!(vendor.monster_type, MonsterType::Vendor);
+        assert_eq!((vendor.hp, vendor.damage), (15, 7));
+    }
+
+    #[test]
+    fn parse_custom_monster() {
+        let text = format!("Cave_Troll A 9 4 0 2 regen,multi:3\n{}", VENDOR);
+        let table = MonsterTable::parse(&text).unwrap();
+        let def = &table.spawnable()[0];
+
+        assert_eq!(def.monster_type, MonsterType::Custom(0));
+        assert_eq!(def.name, "Cave Troll");
+        assert_eq!(def.spawn_weight, 2);
+        assert!(def.has_flag(MonsterFlag::Regenerate));
+        assert!(def.has_flag(MonsterFlag::Multi(3)));
+    }
+
+    #[test]
+    fn parse_rejects_bad_lines() {
+        let bad = [
+            ("Orc AN 4 2 0 1\n", 1),                       // Missing flags
+            ("Orc AN 4 2 0 1 - extra\n", 1),               // Too many fields
+            ("Orc AN four 2 0 1 -\n", 1),                  // Not a number
+            ("Orc AN 4 2 0 1 flying\n", 1),                // Unknown flag
+            ("Orc AN 4 2 0 1 multi:0\n", 1),               // No attacks
+            ("Orc AN 4 2 0 1 -\n\nOrc AN 4 2 0 1 -\n", 3), // Same monster twice
+        ];
+
+        for (text, line) in bad {
+            let text = format!("{}{}", text, VENDOR);
+
+            assert_eq!(
+                MonsterTable::parse(&text).err(),
+                Some(Error::BadData(line)),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn parse_needs_a_monster_and_a_vendor() {
+        assert_eq!(MonsterTable::parse(VENDOR).err(), Some(Error::DataFile));
+        assert_eq!(
+            MonsterTable::parse("Orc AN 4 2 0 1 -\n").err(),
+            Some(Error::DataFile)
+        );
+        assert_eq!(
+            MonsterTable::parse(&format!("Orc AN 4 2 0 0 -\n{}", VENDOR)).err(),
+            Some(Error::DataFile)
+        );
+    }
 }
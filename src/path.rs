@@ -0,0 +1,251 @@
+use std::collections::VecDeque;
+
+use crate::game::{Direction, Stairs};
+use crate::view::RememberedRoom;
+
+/// A single step along a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathStep {
+    Move(Direction),
+    Stairs(Stairs),
+}
+
+/// A room location and the step that led there
+type Breadcrumb = ((u32, u32, u32), PathStep);
+
+/// True if the player can safely walk through a room of this type
+///
+/// Monsters and vendors stop the player, and sinkholes and warps throw them
+/// somewhere else.
+pub fn safe_to_cross(room_type: RememberedRoom) -> bool {
+    !matches!(
+        room_type,
+        RememberedRoom::Monster(_) | RememberedRoom::Sinkhole | RememberedRoom::Warp
+    )
+}
+
+/// Find the shortest path from a start location to the nearest goal
+///
+/// `known` returns the type of the room at a location if the player knows
+/// it, and `is_goal` decides if a location is a destination. Rooms along the
+/// way have to be known and safe to cross, but goals can be anything.
+///
+/// Moving off the edge of a level wraps around to the other side, and known
+/// stairs connect to the level above or below.
+pub fn find_path<K, G>(
+    size: (u32, u32, u32),
+    start: (u32, u32, u32),
+    known: K,
+    is_goal: G,
+) -> Option<Vec<PathStep>>
+where
+    K: Fn(u32, u32, u32) -> Option<RememberedRoom>,
+    G: Fn(u32, u32, u32) -> bool,
+{
+    let (xsize, ysize, zsize) = size;
+
+    let index = |x: u32, y: u32, z: u32| ((z * ysize + y) * xsize + x) as usize;
+
+    // For each room, the room we came from and the step that got us here
+    let mut came_from: Vec<Option<Breadcrumb>> = vec![None; (xsize * ysize * zsize) as usize];
+    let mut visited = vec![false; (xsize * ysize * zsize) as usize];
+
+    let mut queue = VecDeque::new();
+
+    visited[index(start.0, start.1, start.2)] = true;
+    queue.push_back(start);
+
+    while let Some((x, y, z)) = queue.pop_front() {
+        if is_goal(x, y, z) {
+            // Walk back to the start to build the path
+            let mut path = Vec::new();
+            let mut at = (x, y, z);
+
+            while let Some((prev, step)) = came_from[index(at.0, at.1, at.2)] {
+                path.push(step);
+                at = prev;
+            }
+
+            path.reverse();
+
+            return Some(path);
+        }
+
+        // Only the start room and safe known rooms lead anywhere else
+        let room_type = known(x, y, z);
+
+        if (x, y, z) != start && !room_type.is_some_and(safe_to_cross) {
+            continue;
+        }
+
+        let (north, south) = ((y + ysize - 1) % ysize, (y + 1) % ysize);
+        let (west, east) = ((x + xsize - 1) % xsize, (x + 1) % xsize);
+
+        let mut neighbors = vec![
+            (x, north, z, PathStep::Move(Direction::North)),
+            (x, south, z, PathStep::Move(Direction::South)),
+            (west, y, z, PathStep::Move(Direction::West)),
+            (east, y, z, PathStep::Move(Direction::East)),
+        ];
+
+        match room_type {
+            // North out of the entrance leaves the castle
+            Some(RememberedRoom::Entrance) => {
+                neighbors.remove(0);
+            }
+            Some(RememberedRoom::StairsDown) if z + 1 < zsize => {
+                neighbors.push((x, y, z + 1, PathStep::Stairs(Stairs::Down)));
+            }
+            Some(RememberedRoom::StairsUp) if z > 0 => {
+                neighbors.push((x, y, z - 1, PathStep::Stairs(Stairs::Up)));
+            }
+            _ => (),
+        }
+
+        for (nx, ny, nz, step) in neighbors {
+            let i = index(nx, ny, nz);
+
+            if visited[i] {
+                continue;
+            }
+
+            visited[i] = true;
+            came_from[i] = Some(((x, y, z), step));
+            queue.push_back((nx, ny, nz));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monster::MonsterType;
+
+    const SIZE: (u32, u32, u32) = (5, 4, 2);
+
+    /// A castle where the player knows every room, all of them empty
+    /// except for a few
+    fn castle(rooms: &[((u32, u32, u32), RememberedRoom)]) -> Vec<Option<RememberedRoom>> {
+        let mut known = vec![Some(RememberedRoom::Empty); (SIZE.0 * SIZE.1 * SIZE.2) as usize];
+
+        for &((x, y, z), room_type) in rooms {
+            known[((z * SIZE.1 + y) * SIZE.0 + x) as usize] = Some(room_type);
+        }
+
+        known
+    }
+
+    /// Find a path through a castle to a single goal
+    fn path(
+        known: &[Option<RememberedRoom>],
+        start: (u32, u32, u32),
+        goal: (u32, u32, u32),
+    ) -> Option<Vec<PathStep>> {
+        find_path(
+            SIZE,
+            start,
+            |x, y, z| known[((z * SIZE.1 + y) * SIZE.0 + x) as usize],
+            |x, y, z| (x, y, z) == goal,
+        )
+    }
+
+    #[test]
+    fn reaches_target() {
+        let known = castle(&[]);
+
+        assert_eq!(
+            path(&known, (0, 1, 0), (2, 1, 0)),
+            Some(vec![
+                PathStep::Move(Direction::East),
+                PathStep::Move(Direction::East)
+            ])
+        );
+
+        // Off the west edge wraps around to the east
+        assert_eq!(
+            path(&known, (0, 1, 0), (4, 1, 0)),
+            Some(vec![PathStep::Move(Direction::West)])
+        );
+    }
+
+    #[test]
+    fn start_is_goal() {
+        let known = castle(&[]);
+
+        assert_eq!(path(&known, (1, 1, 0), (1, 1, 0)), Some(Vec::new()));
+    }
+
+    #[test]
+    fn no_path_past_danger() {
+        // Every way out of (1,1) is blocked, and the goal below can't be
+        // reached without stairs
+        let known = castle(&[
+            ((1, 0, 0), RememberedRoom::Monster(MonsterType::Orc)),
+            ((1, 2, 0), RememberedRoom::Sinkhole),
+            ((0, 1, 0), RememberedRoom::Warp),
+            ((2, 1, 0), RememberedRoom::Monster(MonsterType::Vendor)),
+        ]);
+
+        assert_eq!(path(&known, (1, 1, 0), (3, 3, 0)), None);
+        assert_eq!(path(&known, (0, 0, 0), (1, 1, 1)), None);
+    }
+
+    #[test]
+    fn unknown_rooms_block_the_way() {
+        let mut known = castle(&[
+            ((2, 0, 0), RememberedRoom::StairsDown),
+            ((2, 0, 1), RememberedRoom::StairsUp),
+        ]);
+
+        // Forget the bottom level except for the stairs
+        for (i, room) in known.iter_mut().enumerate() {
+            if i >= (SIZE.0 * SIZE.1) as usize && *room == Some(RememberedRoom::Empty) {
+                *room = None;
+            }
+        }
+
+        // The goal itself can be unknown, but not the rooms on the way
+        assert_eq!(
+            path(&known, (2, 0, 1), (2, 1, 1)),
+            Some(vec![PathStep::Move(Direction::South)])
+        );
+        assert_eq!(path(&known, (2, 0, 1), (2, 2, 1)), None);
+
+        // A forgotten room on the top level is walked around, here the other
+        // way past the edge
+        known[(SIZE.0 + 1) as usize] = None;
+
+        assert_eq!(
+            path(&known, (0, 1, 0), (2, 1, 0)),
+            Some(vec![PathStep::Move(Direction::West); 3])
+        );
+    }
+
+    #[test]
+    fn takes_stairs_between_levels() {
+        let known = castle(&[
+            ((2, 0, 0), RememberedRoom::StairsDown),
+            ((2, 0, 1), RememberedRoom::StairsUp),
+        ]);
+
+        assert_eq!(
+            path(&known, (1, 0, 0), (2, 1, 1)),
+            Some(vec![
+                PathStep::Move(Direction::East),
+                PathStep::Stairs(Stairs::Down),
+                PathStep::Move(Direction::South),
+            ])
+        );
+
+        assert_eq!(
+            path(&known, (2, 1, 1), (1, 0, 0)),
+            Some(vec![
+                PathStep::Move(Direction::North),
+                PathStep::Stairs(Stairs::Up),
+                PathStep::Move(Direction::West),
+            ])
+        );
+    }
+}
@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 
 use crate::armor::{Armor, ArmorType};
-use crate::curse::CurseType;
+use crate::curse::{Curse, CurseDef, CurseType};
+use crate::difficulty::Difficulty;
+use crate::equipment::Equipment;
 use crate::error::Error;
+use crate::inventory::{Inventory, Item, PLAYER_CAPACITY};
+use crate::ring::{Ring, RingType};
+use crate::shield::{Shield, ShieldType};
 use crate::treasure::TreasureType;
 use crate::weapon::{Weapon, WeaponType};
 
@@ -43,20 +48,15 @@ pub struct Player {
 
     armor: Armor,
     weapon: Weapon,
+    shield: Shield,
+    ring: Ring,
 
-    lamp: bool,
-
-    treasures: Vec<TreasureType>,
-
-    runestaff: bool,
-    orb_of_zot: bool,
-
-    flares: u32,
+    inventory: Inventory,
 
     blind: bool,
     book_stuck: bool,
 
-    curses: Vec<CurseType>,
+    curses: Vec<Curse>,
 }
 
 impl Default for self::Player {
@@ -86,13 +86,9 @@ impl Player {
 
             armor: Armor::new(ArmorType::None),
             weapon: Weapon::new(WeaponType::None),
-            lamp: false,
-            treasures: Vec::new(),
-
-            orb_of_zot: false,
-            runestaff: false,
-
-            flares: 0,
+            shield: Shield::new(ShieldType::None),
+            ring: Ring::new(RingType::None),
+            inventory: Inventory::new(PLAYER_CAPACITY),
 
             curses: Vec::new(),
         }
@@ -106,26 +102,22 @@ impl Player {
     }
 
     /// Set the race and all the corresponding points
-    pub fn init(&mut self, race: Race) {
+    pub fn init(&mut self, race: Race, difficulty: Difficulty) {
         let race_id = Player::get_id_by_race(race);
 
         self.stat.insert(Stat::Strength, 2 + (race_id + 1) * 2);
         self.stat.insert(Stat::Dexterity, 14 - (race_id + 1) * 2);
         self.stat.insert(Stat::Intelligence, 8);
 
-        if race == Race::Hobbit {
-            self.additional_points = 4;
-        } else {
-            self.additional_points = 8;
-        }
+        let usual_points = if race == Race::Hobbit { 4 } else { 8 };
 
-        self.race = race;
+        self.additional_points = difficulty.bonus_points(usual_points);
 
-        self.gp = 60;
+        self.race = race;
 
-        self.flares = 0;
+        self.gp = difficulty.starting_gp();
 
-        self.treasures.clear();
+        self.inventory.clear();
         self.curses.clear();
     }
 
@@ -192,71 +184,14 @@ impl Player {
         *self.stat(&stat) == 18
     }
 
-    /// Give the player some armor
-    pub fn purchase_armor(&mut self, a: ArmorType, is_vendor: bool) -> Result<(), Error> {
-        let armor_cost = Armor::cost(a, is_vendor);
-
-        if armor_cost > self.gp {
-            return Err(Error::NotEnoughGP);
-        }
-
-        self.armor = Armor::new(a);
-
-        self.gp -= armor_cost;
-
-        Ok(())
-    }
-
-    /// Give the player a weapon
-    pub fn purchase_weapon(&mut self, w: WeaponType, is_vendor: bool) -> Result<(), Error> {
-        let weapon_cost = Weapon::cost(w, is_vendor);
-
-        if weapon_cost > self.gp {
-            return Err(Error::NotEnoughGP);
-        }
-
-        self.weapon = Weapon::new(w);
-
-        self.gp -= weapon_cost;
-
-        Ok(())
-    }
-
-    /// True if the player can afford a lamp
-    pub fn can_purchase_lamp(&self) -> bool {
-        self.gp >= 20
-    }
-
-    /// Purchase a lamp
-    pub fn purchase_lamp(&mut self, lamp: bool) -> Result<(), Error> {
-        if !self.can_purchase_lamp() {
-            return Err(Error::NotEnoughGP);
-        }
-
-        self.lamp = lamp;
-
-        if lamp {
-            self.gp -= 20;
-        }
-
-        Ok(())
-    }
-
-    /// Return the max number of flares a player can afford
-    pub fn max_flares(&self) -> u32 {
-        self.gp
-    }
-
-    /// Purchase flares
-    pub fn purchase_flares(&mut self, flares: u32) -> Result<(), Error> {
-        if flares > self.max_flares() {
-            return Err(Error::NotEnoughGP);
+    /// Put on or wield a piece of equipment, replacing what's in its slot
+    pub fn equip(&mut self, equipment: Equipment) {
+        match equipment {
+            Equipment::Shield(s) => self.shield = Shield::new(s),
+            Equipment::Ring(r) => self.ring = Ring::new(r),
+            Equipment::EnchantedWeapon(w, bonus) => self.weapon = Weapon::enchanted(w, bonus),
+            Equipment::ElvenArmor(a) => self.armor = Armor::elven(a),
         }
-
-        self.flares += flares;
-        self.gp -= flares;
-
-        Ok(())
     }
 
     /// Return true if the player is blind
@@ -276,7 +211,7 @@ impl Player {
 
     /// Return flare count
     pub fn flares(&self) -> u32 {
-        self.flares
+        self.inventory.count(Item::Flare)
     }
 
     /// Return number of gold pieces
@@ -316,6 +251,16 @@ impl Player {
         self.armor = Armor::new(armor_type);
     }
 
+    /// Return player's shield
+    pub fn shield(&self) -> &Shield {
+        &self.shield
+    }
+
+    /// Return player's ring
+    pub fn ring(&self) -> &Ring {
+        &self.ring
+    }
+
     /// Return player's armor mutably
     pub fn armor_mut(&mut self) -> &mut Armor {
         &mut self.armor
@@ -323,12 +268,34 @@ impl Player {
 
     /// True if the player has a lamp
     pub fn has_lamp(&self) -> bool {
-        self.lamp
+        self.inventory.has(Item::Lamp)
     }
 
     /// Set if the player has a lamp
-    pub fn set_lamp(&mut self, has_lamp: bool) {
-        self.lamp = has_lamp;
+    pub fn set_lamp(&mut self, has_lamp: bool) -> Result<(), Error> {
+        self.set_item(Item::Lamp, has_lamp)
+    }
+
+    /// Give or take away a single item
+    ///
+    /// Giving fails if there's no room for it.
+    fn set_item(&mut self, item: Item, has: bool) -> Result<(), Error> {
+        if has {
+            self.inventory.add(item, 1)
+        } else {
+            self.inventory.remove(item, 1);
+            Ok(())
+        }
+    }
+
+    /// Return the player's inventory
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    /// Return the player's inventory mutably
+    pub fn inventory_mut(&mut self) -> &mut Inventory {
+        &mut self.inventory
     }
 
     /// Damage the player
@@ -356,6 +323,31 @@ impl Player {
         armor_destroyed
     }
 
+    /// Wear down the weapon
+    ///
+    /// Return true if the weapon breaks
+    pub fn wear_weapon(&mut self, amount: u32) -> bool {
+        let weapon_broke =
+            self.weapon.wear_down(amount) && self.weapon.weapon_type() != WeaponType::None;
+
+        if weapon_broke {
+            self.weapon = Weapon::new(WeaponType::None);
+        }
+
+        weapon_broke
+    }
+
+    /// Return the total wear on the player's weapon and armor
+    pub fn gear_wear(&self) -> u32 {
+        self.weapon.wear() + self.armor.wear()
+    }
+
+    /// Restore the player's weapon and armor to new
+    pub fn repair_gear(&mut self) {
+        self.weapon.repair();
+        self.armor.repair();
+    }
+
     /// Returns true if the player is dead
     pub fn is_dead(&self) -> bool {
         *self.stat(&Stat::Strength) == 0
@@ -365,46 +357,46 @@ impl Player {
 
     /// True if the player has the Orb of Zot
     pub fn has_orb_of_zot(&self) -> bool {
-        self.orb_of_zot
+        self.inventory.has(Item::OrbOfZot)
     }
 
     /// Give the Orb of Zot to the player
-    pub fn give_orb_of_zot(&mut self, has: bool) {
-        self.orb_of_zot = has;
+    pub fn give_orb_of_zot(&mut self, has: bool) -> Result<(), Error> {
+        self.set_item(Item::OrbOfZot, has)
     }
 
     /// True if the player has the Runestaff
     pub fn has_runestaff(&self) -> bool {
-        self.runestaff
+        self.inventory.has(Item::Runestaff)
     }
 
     /// Give the runestaff to the player
-    pub fn give_runestaff(&mut self, has: bool) {
-        self.runestaff = has;
+    pub fn give_runestaff(&mut self, has: bool) -> Result<(), Error> {
+        self.set_item(Item::Runestaff, has)
     }
 
     /// Return a list of players treasures
     pub fn get_treasures(&self) -> Vec<TreasureType> {
-        self.treasures.clone()
+        self.inventory
+            .stacks()
+            .iter()
+            .filter_map(|s| match s.item {
+                Item::Treasure(t) => Some(t),
+                _ => None,
+            })
+            .collect()
     }
 
     /// Remove a specific treasure from the list
     ///
     /// Returns true on success (if the player had the treasure)
     pub fn remove_treasure(&mut self, treasure_type: TreasureType) -> bool {
-        // Find the element
-        match self.treasures.iter().position(|&t| t == treasure_type) {
-            Some(i) => {
-                self.treasures.remove(i);
-                true
-            }
-            None => false,
-        }
+        self.inventory.remove(Item::Treasure(treasure_type), 1) > 0
     }
 
     /// True if the player has a specific treasure
     pub fn has_treasure(&self, treasure_type: TreasureType) -> bool {
-        self.treasures.contains(&treasure_type)
+        self.inventory.has(Item::Treasure(treasure_type))
     }
 
     /// Spend some GP
@@ -476,24 +468,11 @@ impl Player {
         &self.additional_points
     }
 
-    /// Give a treasure to the player
-    pub fn treasure_add(&mut self, treasure: TreasureType) {
-        if self.treasures.contains(&treasure) {
-            return;
-        }
+    /// Use up some flares
+    pub fn use_flares(&mut self, count: u32) -> u32 {
+        self.inventory.remove(Item::Flare, count);
 
-        self.treasures.push(treasure);
-    }
-
-    /// change flares value
-    pub fn change_flares(&mut self, delta: i32) -> u32 {
-        let mut f = self.flares as i32;
-
-        f = std::cmp::max(0, f + delta);
-
-        self.flares = f as u32;
-
-        self.flares
+        self.flares()
     }
 
     /// True if a book is stuck to the player's hands
@@ -506,20 +485,64 @@ impl Player {
         self.book_stuck = stuck;
     }
 
-    /// Curse the player
-    pub fn add_curse(&mut self, curse: CurseType) {
-        if curse != CurseType::None && !self.curses.contains(&curse) {
-            self.curses.push(curse);
+    /// Curse the player, or make an existing curse worse
+    pub fn add_curse(&mut self, def: &CurseDef) {
+        let curse_type = def.curse_type;
+
+        match self.curses.iter_mut().find(|c| c.curse_type == curse_type) {
+            Some(curse) => curse.stack(def),
+            None => self.curses.push(Curse::new(def)),
         }
     }
 
     /// True if the player has a curse
     pub fn has_curse(&self, curse: CurseType) -> bool {
-        self.curses.contains(&curse)
+        self.curse_stacks(curse) > 0
+    }
+
+    /// Return how many times the player has caught a curse
+    pub fn curse_stacks(&self, curse: CurseType) -> u32 {
+        match self.curses.iter().find(|c| c.curse_type == curse) {
+            Some(c) => c.stacks,
+            None => 0,
+        }
+    }
+
+    /// Return the player's curses
+    pub fn curses(&self) -> &[Curse] {
+        &self.curses
+    }
+
+    /// Lift a curse
+    ///
+    /// Returns true if the player had the curse
+    pub fn remove_curse(&mut self, curse: CurseType) -> bool {
+        let count = self.curses.len();
+
+        self.curses.retain(|c| c.curse_type != curse);
+
+        self.curses.len() != count
+    }
+
+    /// Let a turn pass for every curse
+    ///
+    /// Returns the curses that ran their course and were lifted
+    pub fn tick_curses(&mut self) -> Vec<CurseType> {
+        let mut expired = Vec::new();
+
+        for curse in self.curses.iter_mut() {
+            if curse.tick() {
+                expired.push(curse.curse_type);
+            }
+        }
+
+        self.curses.retain(|c| !expired.contains(&c.curse_type));
+
+        expired
     }
 
     /// True if the player has any treasure
     pub fn has_any_treasure(&self) -> bool {
-        !self.treasures.is_empty()
+        !self.get_treasures().is_empty()
     }
 }
@@ -0,0 +1,44 @@
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum RingType {
+    None,
+    Protection,
+    Accuracy,
+    Regeneration,
+}
+
+pub struct Ring {
+    ring_type: RingType,
+}
+
+impl Ring {
+    /// Create a new ring
+    pub fn new(r: RingType) -> Ring {
+        Ring { ring_type: r }
+    }
+
+    /// Return protection added on top of armor
+    pub fn armor_value(&self) -> u32 {
+        match self.ring_type {
+            RingType::Protection => 1,
+            _ => 0,
+        }
+    }
+
+    /// Return the bonus to hit in melee
+    pub fn hit_bonus(&self) -> u32 {
+        match self.ring_type {
+            RingType::Accuracy => 3,
+            _ => 0,
+        }
+    }
+
+    /// True if the ring slowly restores strength
+    pub fn regenerates(&self) -> bool {
+        self.ring_type == RingType::Regeneration
+    }
+
+    /// Return ring type
+    pub fn ring_type(&self) -> RingType {
+        self.ring_type
+    }
+}
@@ -1,4 +1,5 @@
 use crate::curse::CurseType;
+use crate::inventory::Inventory;
 use crate::monster::Monster;
 use crate::treasure::Treasure;
 
@@ -25,6 +26,8 @@ pub struct Room {
     pub roomtype: RoomType,
     pub discovered: bool,
     pub curse: CurseType,
+    pub items: Inventory, // Things left lying here
+    pub cleared: bool,    // The player dealt with what was here
 }
 
 impl Default for Room {
@@ -33,6 +36,8 @@ impl Default for Room {
             roomtype: RoomType::Empty,
             discovered: false,
             curse: CurseType::None,
+            items: Inventory::unlimited(),
+            cleared: false,
         }
     }
 }
@@ -43,6 +48,12 @@ impl Room {
         self.roomtype = RoomType::Empty;
     }
 
+    /// Mark the room as empty because the player dealt with what was here
+    pub fn clear(&mut self) {
+        self.make_empty();
+        self.cleared = true;
+    }
+
     /// Get the room type
     pub fn room_type(&self) -> &RoomType {
         &self.roomtype
@@ -58,6 +69,16 @@ impl Room {
         self.curse = curse_type;
     }
 
+    /// Returns the items lying in the room
+    pub fn items(&self) -> &Inventory {
+        &self.items
+    }
+
+    /// Returns the items lying in the room mutably
+    pub fn items_mut(&mut self) -> &mut Inventory {
+        &mut self.items
+    }
+
     /// Returns the room's curse status
     pub fn curse(&self) -> &CurseType {
         &self.curse
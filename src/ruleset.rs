@@ -0,0 +1,174 @@
+use crate::data;
+use crate::error::Error;
+
+/// The places this game departs from the 1980 original, each of which can be
+/// played either way
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct Ruleset {
+    pub gas_destroys_chest: bool,  // Opening a chest of gas destroys it
+    pub hide_bat_when_blind: bool, // Blind players miss the bat, not step on a frog
+    pub intro: bool,               // Tell the story from the magazine article
+    pub help: bool,                // Offer a help command
+    pub x_east_west: bool,         // X runs east-west, not north-south
+    pub abilities: bool,           // Monsters use the abilities in their flags
+    pub curse_warning: bool,       // Players are told when they catch a curse
+    pub chest_gear: bool,          // Some chests hold gear instead of gold
+    pub weapon_wear: bool,         // Weapons wear out with use and can break
+    pub pack_limit: bool,          // The pack only holds so many kinds of thing
+    pub curses_leave_rooms: bool,  // A caught curse is gone from its room
+}
+
+impl Ruleset {
+    /// Return the rules of the 1980 original
+    pub fn classic() -> Ruleset {
+        Ruleset {
+            gas_destroys_chest: false,
+            hide_bat_when_blind: false,
+            intro: false,
+            help: false,
+            x_east_west: false,
+            abilities: false,
+            curse_warning: false,
+            chest_gear: false,
+            weapon_wear: false,
+            pack_limit: false,
+            curses_leave_rooms: false,
+        }
+    }
+
+    /// Return the rules of this version
+    pub fn modern() -> Ruleset {
+        Ruleset {
+            gas_destroys_chest: true,
+            hide_bat_when_blind: true,
+            intro: true,
+            help: true,
+            x_east_west: true,
+            abilities: true,
+            curse_warning: true,
+            chest_gear: true,
+            weapon_wear: true,
+            pack_limit: true,
+            curses_leave_rooms: true,
+        }
+    }
+
+    /// Return the preset with a given name
+    pub fn preset(name: &str) -> Option<Ruleset> {
+        match name {
+            "classic" => Some(Ruleset::classic()),
+            "modern" => Some(Ruleset::modern()),
+            _ => None,
+        }
+    }
+
+    /// Load rules from a data file
+    pub fn load(path: &str) -> Result<Ruleset, Error> {
+        Ruleset::parse(&data::read_file(path)?)
+    }
+
+    /// Parse rules data
+    ///
+    /// Settings go under a `[rules]` section. A `preset` setting of `classic`
+    /// or `modern` picks the starting rules, modern if it's left out, and
+    /// each rule after that is set to `yes` or `no`. An `[economy]` section
+    /// is left for `Economy` to read, and settings anywhere else are an error.
+    pub fn parse(text: &str) -> Result<Ruleset, Error> {
+        let mut rules = Ruleset::modern();
+
+        for setting in data::settings(text)? {
+            let bad = Error::BadData(setting.line);
+
+            match setting.section {
+                "rules" => (),
+                "economy" => continue,
+                _ => return Err(bad),
+            }
+
+            if setting.key == "preset" {
+                rules = Ruleset::preset(setting.value).ok_or(bad)?;
+                continue;
+            }
+
+            let value = setting.bool()?;
+
+            match setting.key {
+                "gas_destroys_chest" => rules.gas_destroys_chest = value,
+                "hide_bat_when_blind" => rules.hide_bat_when_blind = value,
+                "intro" => rules.intro = value,
+                "help" => rules.help = value,
+                "x_east_west" => rules.x_east_west = value,
+                "abilities" => rules.abilities = value,
+                "curse_warning" => rules.curse_warning = value,
+                "chest_gear" => rules.chest_gear = value,
+                "weapon_wear" => rules.weapon_wear = value,
+                "pack_limit" => rules.pack_limit = value,
+                "curses_leave_rooms" => rules.curses_leave_rooms = value,
+                _ => return Err(bad),
+            }
+        }
+
+        Ok(rules)
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Ruleset {
+        Ruleset::modern()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        assert_eq!(Ruleset::preset("classic"), Some(Ruleset::classic()));
+        assert_eq!(Ruleset::preset("modern"), Some(Ruleset::modern()));
+        assert_eq!(Ruleset::preset("ancient"), None);
+        assert_eq!(Ruleset::default(), Ruleset::modern());
+    }
+
+    #[test]
+    fn parse_starts_from_preset() {
+        assert_eq!(Ruleset::parse(""), Ok(Ruleset::modern()));
+
+        let text = "# Mostly classic\n[rules]\npreset = classic\nhelp = yes\n";
+        let rules = Ruleset::parse(text).unwrap();
+
+        assert_eq!(
+            rules,
+            Ruleset {
+                help: true,
+                ..Ruleset::classic()
+            }
+        );
+
+        let rules = Ruleset::parse("[rules]\nintro = no\n").unwrap();
+
+        assert_eq!(
+            rules,
+            Ruleset {
+                intro: false,
+                ..Ruleset::modern()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_settings() {
+        let bad = [
+            ("[rules]\npreset = ancient\n", 2),   // Unknown preset
+            ("[rules]\nhelp = maybe\n", 2),       // Not yes or no
+            ("[rules]\n\nfog_of_war = yes\n", 3), // Unknown rule
+            ("help = yes\n", 1),                  // No section
+            ("[monsters]\nhelp = yes\n", 2),      // Wrong section
+            ("[rules]\nhelp\n", 2),               // Not a setting
+        ];
+
+        for (text, line) in bad {
+            assert_eq!(Ruleset::parse(text), Err(Error::BadData(line)), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,33 @@
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ShieldType {
+    None,
+    Buckler,
+    Kite,
+    Tower,
+}
+
+pub struct Shield {
+    shield_type: ShieldType,
+}
+
+impl Shield {
+    /// Create a new shield
+    pub fn new(s: ShieldType) -> Shield {
+        Shield { shield_type: s }
+    }
+
+    /// Return the percent chance of blocking a monster's blow
+    pub fn block_chance(&self) -> u32 {
+        match self.shield_type {
+            ShieldType::None => 0,
+            ShieldType::Buckler => 10,
+            ShieldType::Kite => 20,
+            ShieldType::Tower => 30,
+        }
+    }
+
+    /// Return shield type
+    pub fn shield_type(&self) -> ShieldType {
+        self.shield_type
+    }
+}
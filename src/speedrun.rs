@@ -0,0 +1,73 @@
+use std::time::{Duration, Instant};
+
+/// A milestone the speedrun timer records
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Split {
+    Runestaff,
+    OrbOfZot,
+    Exit,
+}
+
+/// A wall-clock timer for racing through the castle
+#[derive(Debug, Clone)]
+pub struct Speedrun {
+    start: Instant,
+    splits: Vec<(Split, Duration)>,
+}
+
+impl Speedrun {
+    /// Start the clock
+    pub fn start() -> Speedrun {
+        Speedrun {
+            start: Instant::now(),
+            splits: Vec::new(),
+        }
+    }
+
+    /// Return the time since the clock started
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Record the time a milestone was reached
+    ///
+    /// Only the first time counts. Returns the split time if this was it.
+    pub fn split(&mut self, split: Split) -> Option<Duration> {
+        if self.splits.iter().any(|(s, _)| *s == split) {
+            return None;
+        }
+
+        let time = self.elapsed();
+
+        self.splits.push((split, time));
+
+        Some(time)
+    }
+
+    /// Return the milestones reached, in order, with their times
+    pub fn splits(&self) -> &[(Split, Duration)] {
+        &self.splits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_first_split_counts() {
+        let mut speedrun = Speedrun::start();
+
+        let runestaff = speedrun.split(Split::Runestaff).unwrap();
+        assert_eq!(speedrun.split(Split::Runestaff), None);
+
+        let exit = speedrun.split(Split::Exit).unwrap();
+
+        assert_eq!(
+            speedrun.splits(),
+            &[(Split::Runestaff, runestaff), (Split::Exit, exit)]
+        );
+        assert!(runestaff <= exit);
+        assert!(exit <= speedrun.elapsed());
+    }
+}
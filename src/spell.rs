@@ -0,0 +1,100 @@
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
+pub enum SpellType {
+    Web,
+    Fireball,
+    Deathspell,
+    Heal,
+    Light,
+    DetectMonsters,
+    Blink,
+    RemoveCurse,
+}
+
+/// What it takes to cast a spell, and when it can be cast
+#[derive(Debug, Copy, Clone)]
+pub struct Spell {
+    pub spell_type: SpellType,
+    pub min_iq: u32,   // Intelligence needed to know the spell
+    pub st_cost: u32,  // Strength lost casting it
+    pub iq_cost: u32,  // Intelligence lost casting it
+    pub combat: bool,  // Can be cast during combat
+    pub explore: bool, // Can be cast outside of combat
+}
+
+/// Every spell in the game
+pub const SPELLS: [Spell; 8] = [
+    Spell {
+        spell_type: SpellType::Web,
+        min_iq: 15,
+        st_cost: 1,
+        iq_cost: 0,
+        combat: true,
+        explore: false,
+    },
+    Spell {
+        spell_type: SpellType::Fireball,
+        min_iq: 15,
+        st_cost: 1,
+        iq_cost: 1,
+        combat: true,
+        explore: false,
+    },
+    Spell {
+        spell_type: SpellType::Deathspell,
+        min_iq: 15,
+        st_cost: 0,
+        iq_cost: 0,
+        combat: true,
+        explore: false,
+    },
+    Spell {
+        spell_type: SpellType::Heal,
+        min_iq: 12,
+        st_cost: 0,
+        iq_cost: 2,
+        combat: true,
+        explore: true,
+    },
+    Spell {
+        spell_type: SpellType::Light,
+        min_iq: 10,
+        st_cost: 0,
+        iq_cost: 1,
+        combat: false,
+        explore: true,
+    },
+    Spell {
+        spell_type: SpellType::DetectMonsters,
+        min_iq: 14,
+        st_cost: 0,
+        iq_cost: 2,
+        combat: false,
+        explore: true,
+    },
+    Spell {
+        spell_type: SpellType::Blink,
+        min_iq: 12,
+        st_cost: 1,
+        iq_cost: 1,
+        combat: false,
+        explore: true,
+    },
+    Spell {
+        spell_type: SpellType::RemoveCurse,
+        min_iq: 14,
+        st_cost: 1,
+        iq_cost: 1,
+        combat: false,
+        explore: true,
+    },
+];
+
+impl Spell {
+    /// Return the spell of a given type
+    pub fn get(spell_type: SpellType) -> &'static Spell {
+        SPELLS
+            .iter()
+            .find(|s| s.spell_type == spell_type)
+            .expect("every spell type is in SPELLS")
+    }
+}
@@ -0,0 +1,91 @@
+use std::ops::AddAssign;
+
+/// Exploration and content counts for a level or the whole castle
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Stats {
+    pub rooms: u32,
+    pub discovered: u32,
+    pub monsters: u32,
+    pub vendors: u32,
+    pub treasures: u32,
+    pub items: u32,
+    pub stairs: u32,
+    pub stairs_found: u32,
+    pub cleared: u32,
+}
+
+impl Stats {
+    /// Return the percentage of rooms discovered, rounded down
+    pub fn percent_discovered(&self) -> u32 {
+        if self.rooms == 0 {
+            return 0;
+        }
+
+        self.discovered * 100 / self.rooms
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, other: Stats) {
+        self.rooms += other.rooms;
+        self.discovered += other.discovered;
+        self.monsters += other.monsters;
+        self.vendors += other.vendors;
+        self.treasures += other.treasures;
+        self.items += other.items;
+        self.stairs += other.stairs;
+        self.stairs_found += other.stairs_found;
+        self.cleared += other.cleared;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_discovered_rounds_down() {
+        let stats = Stats {
+            rooms: 64,
+            discovered: 30,
+            ..Stats::default()
+        };
+
+        assert_eq!(stats.percent_discovered(), 46);
+        assert_eq!(Stats::default().percent_discovered(), 0);
+    }
+
+    #[test]
+    fn add_assign_sums_every_count() {
+        let level = Stats {
+            rooms: 64,
+            discovered: 10,
+            monsters: 5,
+            vendors: 1,
+            treasures: 2,
+            items: 8,
+            stairs: 4,
+            stairs_found: 1,
+            cleared: 3,
+        };
+
+        let mut castle = Stats::default();
+        castle += level;
+        castle += level;
+
+        assert_eq!(
+            castle,
+            Stats {
+                rooms: 128,
+                discovered: 20,
+                monsters: 10,
+                vendors: 2,
+                treasures: 4,
+                items: 16,
+                stairs: 8,
+                stairs_found: 2,
+                cleared: 6,
+            }
+        );
+    }
+}
@@ -0,0 +1,247 @@
+extern crate rand;
+
+use self::rand::rng;
+use self::rand::Rng;
+
+use crate::armor::ArmorType;
+use crate::economy::Economy;
+use crate::equipment::{Equipment, VENDOR_GOODS};
+use crate::inventory::Item;
+use crate::player::Stat;
+use crate::weapon::WeaponType;
+
+/// Something a vendor can sell
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Ware {
+    Armor(ArmorType),
+    Weapon(WeaponType),
+    Equipment(Equipment),
+    Potion(Stat),
+    Lamp,
+    Supply(Item), // Consumables, sold by the piece
+}
+
+/// A vendor's goods and gold
+#[derive(Debug, PartialEq, Clone)]
+pub struct Stock {
+    wares: Vec<(Ware, u32)>, // What's for sale, and how many of each
+    purse: u32,
+}
+
+impl Stock {
+    /// Stock a vendor for a given level
+    ///
+    /// Every vendor carries the basics. Vendors deeper in the castle are more
+    /// likely to carry heavy gear and rare goods, and they carry more gold.
+    pub fn new(z: u32, zsize: u32, economy: &Economy) -> Stock {
+        let max_z = std::cmp::max(zsize, 2) - 1;
+        let depth = z * 100 / max_z; // 0 on the top level, 100 on the bottom
+
+        let mut rng = rng();
+
+        let mut stock = Stock {
+            wares: Vec::new(),
+            purse: rng.random_range(1000..=3000) + depth * 50,
+        };
+
+        let flares = rng.random_range(5..=15) + depth / 5;
+
+        let mut roll = |chance: u32| rng.random_range(1..=100) <= chance;
+
+        stock.add(Ware::Armor(ArmorType::Leather), 1);
+        stock.add(Ware::Weapon(WeaponType::Dagger), 1);
+
+        if roll(50 + depth / 2) {
+            stock.add(Ware::Armor(ArmorType::Chainmail), 1);
+        }
+
+        if roll(10 + depth * 2 / 3) {
+            stock.add(Ware::Armor(ArmorType::Plate), 1);
+        }
+
+        if roll(50 + depth / 2) {
+            stock.add(Ware::Weapon(WeaponType::Mace), 1);
+        }
+
+        if roll(10 + depth * 2 / 3) {
+            stock.add(Ware::Weapon(WeaponType::Sword), 1);
+        }
+
+        for e in VENDOR_GOODS {
+            // The pricier the goods, the deeper they turn up
+            let cost = economy.equipment_cost(e);
+
+            if roll((30 + depth / 2).saturating_sub(cost / 100)) {
+                stock.add(Ware::Equipment(e), 1);
+            }
+        }
+
+        for stat in [Stat::Strength, Stat::Intelligence, Stat::Dexterity] {
+            let count = roll(50 + depth / 2) as u32 + roll(depth / 2) as u32;
+
+            stock.add(Ware::Potion(stat), count);
+        }
+
+        if roll(75) {
+            stock.add(Ware::Lamp, 1);
+        }
+
+        // Supplies run deeper down, where getting stuck is worse
+        stock.add(Ware::Supply(Item::Flare), flares);
+
+        for (item, chance) in [
+            (Item::HealingPotion, 60),
+            (Item::SightElixir, 30 + depth / 2),
+            (Item::MappingScroll, 20 + depth / 2),
+            (Item::Solvent, 30 + depth / 2),
+        ] {
+            let count = roll(chance) as u32 + roll(chance / 2) as u32;
+
+            stock.add(Ware::Supply(item), count);
+        }
+
+        stock
+    }
+
+    /// Add wares to the stock
+    fn add(&mut self, ware: Ware, count: u32) {
+        if count == 0 {
+            return;
+        }
+
+        match self.wares.iter_mut().find(|(w, _)| *w == ware) {
+            Some((_, n)) => *n += count,
+            None => self.wares.push((ware, count)),
+        }
+    }
+
+    /// Return everything in stock, and how many of each
+    pub fn wares(&self) -> &[(Ware, u32)] {
+        &self.wares
+    }
+
+    /// Return how many of a ware are in stock
+    pub fn count(&self, ware: Ware) -> u32 {
+        self.wares
+            .iter()
+            .find(|(w, _)| *w == ware)
+            .map_or(0, |(_, n)| *n)
+    }
+
+    /// True if a ware is in stock
+    pub fn has(&self, ware: Ware) -> bool {
+        self.count(ware) > 0
+    }
+
+    /// Take one of a ware out of stock
+    ///
+    /// Return false if there wasn't one to take
+    pub fn take(&mut self, ware: Ware) -> bool {
+        match self.wares.iter().position(|(w, _)| *w == ware) {
+            Some(i) => {
+                self.wares[i].1 -= 1;
+
+                if self.wares[i].1 == 0 {
+                    self.wares.remove(i);
+                }
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Return the gold the vendor has on hand
+    pub fn purse(&self) -> u32 {
+        self.purse
+    }
+
+    /// Add gold to the vendor's purse
+    pub fn receive(&mut self, amount: u32) {
+        self.purse += amount;
+    }
+
+    /// Pay gold out of the vendor's purse
+    ///
+    /// Return false if the vendor can't afford it
+    pub fn pay(&mut self, amount: u32) -> bool {
+        if amount > self.purse {
+            return false;
+        }
+
+        self.purse -= amount;
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_vendor_carries_the_basics() {
+        let economy = Economy::classic();
+
+        for z in 0..8 {
+            let stock = Stock::new(z, 8, &economy);
+
+            assert!(stock.has(Ware::Armor(ArmorType::Leather)), "level {}", z);
+            assert!(stock.has(Ware::Weapon(WeaponType::Dagger)), "level {}", z);
+            assert!(stock.count(Ware::Supply(Item::Flare)) >= 5, "level {}", z);
+            assert!(stock.wares().iter().all(|&(_, n)| n > 0), "level {}", z);
+        }
+    }
+
+    #[test]
+    fn deeper_vendors_carry_more() {
+        let economy = Economy::classic();
+
+        let top = Stock::new(0, 8, &economy);
+        let bottom = Stock::new(7, 8, &economy);
+
+        assert!((1000..=3000).contains(&top.purse()));
+        assert!((6000..=8000).contains(&bottom.purse()));
+
+        assert!(top.count(Ware::Supply(Item::Flare)) <= 15);
+        assert!(bottom.count(Ware::Supply(Item::Flare)) >= 25);
+    }
+
+    #[test]
+    fn taking_the_last_one_empties_the_shelf() {
+        let mut stock = Stock {
+            wares: Vec::new(),
+            purse: 0,
+        };
+
+        stock.add(Ware::Lamp, 2);
+        stock.add(Ware::Lamp, 0);
+        stock.add(Ware::Supply(Item::Solvent), 0);
+
+        assert_eq!(stock.wares(), &[(Ware::Lamp, 2)]);
+
+        assert!(stock.take(Ware::Lamp));
+        assert_eq!(stock.count(Ware::Lamp), 1);
+        assert!(stock.take(Ware::Lamp));
+        assert!(!stock.take(Ware::Lamp));
+
+        assert!(!stock.has(Ware::Lamp));
+        assert!(stock.wares().is_empty());
+    }
+
+    #[test]
+    fn purse_never_goes_negative() {
+        let mut stock = Stock {
+            wares: Vec::new(),
+            purse: 100,
+        };
+
+        assert!(!stock.pay(150));
+        assert_eq!(stock.purse(), 100);
+
+        stock.receive(50);
+
+        assert!(stock.pay(150));
+        assert_eq!(stock.purse(), 0);
+    }
+}
@@ -12,6 +12,25 @@ pub enum TreasureType {
     Silmaril,
 }
 
+impl TreasureType {
+    /// Return the treasure with a given name, ignoring case and a leading "THE"
+    pub fn from_name(name: &str) -> Option<TreasureType> {
+        let upper = name.to_uppercase();
+
+        match upper.trim_start_matches("THE ") {
+            "RUBY RED" => Some(TreasureType::RubyRed),
+            "NORN STONE" => Some(TreasureType::NornStone),
+            "PALE PEARL" => Some(TreasureType::PalePearl),
+            "OPAL EYE" => Some(TreasureType::OpalEye),
+            "GREEN GEM" => Some(TreasureType::GreenGem),
+            "BLUE FLAME" => Some(TreasureType::BlueFlame),
+            "PALANTIR" => Some(TreasureType::Palantir),
+            "SILMARIL" => Some(TreasureType::Silmaril),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Treasure {
     pub treasure_type: TreasureType,
@@ -53,11 +72,10 @@ impl Treasure {
         }
     }
 
-    /// Return maximum value for a treasure
-    pub fn treasure_max_value(treasure_type: TreasureType) -> u32 {
-        let num = Treasure::get_treasure_num_by_type(treasure_type);
-
-        (num + 1) * 1500
+    /// Return how valuable a treasure is, from 1 for the Ruby Red to 8 for
+    /// the Silmaril
+    pub fn rank(treasure_type: TreasureType) -> u32 {
+        Treasure::get_treasure_num_by_type(treasure_type) + 1
     }
 
     /// Return TreasureType for this Treasure
@@ -0,0 +1,196 @@
+use crate::monster::{MonsterType, WoundLevel};
+use crate::room::RoomType;
+use crate::treasure::TreasureType;
+
+/// What the player can tell about a room by looking at it
+///
+/// Unlike `RoomType`, this leaves out everything the player can't see, like
+/// a monster's hit points or which warp hides the Orb of Zot.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum RememberedRoom {
+    Empty,
+    Entrance,
+    StairsDown,
+    StairsUp,
+    Gold,
+    Pool,
+    Chest,
+    Flares,
+    Warp,
+    Sinkhole,
+    CrystalOrb,
+    Book,
+    Monster(MonsterType),
+    Treasure(TreasureType),
+}
+
+impl From<&RoomType> for RememberedRoom {
+    fn from(room_type: &RoomType) -> RememberedRoom {
+        match room_type {
+            RoomType::Empty => RememberedRoom::Empty,
+            RoomType::Entrance => RememberedRoom::Entrance,
+            RoomType::StairsDown => RememberedRoom::StairsDown,
+            RoomType::StairsUp => RememberedRoom::StairsUp,
+            RoomType::Gold => RememberedRoom::Gold,
+            RoomType::Pool => RememberedRoom::Pool,
+            RoomType::Chest => RememberedRoom::Chest,
+            RoomType::Flares => RememberedRoom::Flares,
+            RoomType::Warp(_) => RememberedRoom::Warp,
+            RoomType::Sinkhole => RememberedRoom::Sinkhole,
+            RoomType::CrystalOrb => RememberedRoom::CrystalOrb,
+            RoomType::Book => RememberedRoom::Book,
+            RoomType::Monster(m) => RememberedRoom::Monster(m.monster_type()),
+            RoomType::Treasure(t) => RememberedRoom::Treasure(*t.treasure_type()),
+        }
+    }
+}
+
+/// How a monster the player can see looks
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub struct MonsterSighting {
+    pub wound_level: WoundLevel,
+    pub webbed: bool,
+}
+
+/// What the player remembers seeing in each room
+///
+/// This is kept apart from the dungeon itself, so it goes stale when rooms
+/// change out of the player's sight.
+#[derive(Debug)]
+pub struct Memory {
+    xsize: u32,
+    ysize: u32,
+    rooms: Vec<Option<RememberedRoom>>,
+}
+
+impl Memory {
+    pub fn new(xsize: u32, ysize: u32, zsize: u32) -> Memory {
+        Memory {
+            xsize,
+            ysize,
+            rooms: vec![None; (xsize * ysize * zsize) as usize],
+        }
+    }
+
+    /// Convert a location to an index
+    fn index(&self, x: u32, y: u32, z: u32) -> usize {
+        ((z * self.ysize + y) * self.xsize + x) as usize
+    }
+
+    /// Remember what's in a room
+    pub fn remember(&mut self, x: u32, y: u32, z: u32, room_type: &RoomType) {
+        let i = self.index(x, y, z);
+
+        self.rooms[i] = Some(RememberedRoom::from(room_type));
+    }
+
+    /// Forget what's in a room
+    pub fn forget(&mut self, x: u32, y: u32, z: u32) {
+        let i = self.index(x, y, z);
+
+        self.rooms[i] = None;
+    }
+
+    /// Return what the player remembers about a room, if anything
+    pub fn recall(&self, x: u32, y: u32, z: u32) -> Option<RememberedRoom> {
+        self.rooms[self.index(x, y, z)]
+    }
+}
+
+/// The castle as the player knows it
+///
+/// Front-ends should draw from this rather than the dungeon so that they only
+/// show what the character has actually seen.
+pub struct PlayerView<'a> {
+    memory: &'a Memory,
+    size: (u32, u32, u32),
+    location: (u32, u32, u32),
+    here: RememberedRoom,
+    sighting: Option<MonsterSighting>,
+    blind: bool,
+}
+
+impl<'a> PlayerView<'a> {
+    pub fn new(
+        memory: &'a Memory,
+        size: (u32, u32, u32),
+        location: (u32, u32, u32),
+        here: RememberedRoom,
+        sighting: Option<MonsterSighting>,
+        blind: bool,
+    ) -> PlayerView<'a> {
+        PlayerView {
+            memory,
+            size,
+            location,
+            here,
+            sighting,
+            blind,
+        }
+    }
+
+    /// Return what the player remembers about a room
+    ///
+    /// Returns None if the player doesn't know what's there. The contents
+    /// might be out of date if the room changed since the player saw it.
+    pub fn room_at(&self, x: u32, y: u32, z: u32) -> Option<RememberedRoom> {
+        self.memory.recall(x, y, z)
+    }
+
+    /// True if the player knows what's in a room
+    pub fn is_known(&self, x: u32, y: u32, z: u32) -> bool {
+        self.room_at(x, y, z).is_some()
+    }
+
+    /// Return the player's location
+    pub fn player_location(&self) -> (u32, u32, u32) {
+        self.location
+    }
+
+    /// Return what's in the player's room
+    ///
+    /// The player can always feel out the room they're standing in, even
+    /// blind.
+    pub fn here(&self) -> RememberedRoom {
+        self.here
+    }
+
+    /// True if the player's room holds something they'll want to deal with
+    /// before moving on
+    pub fn here_needs_attention(&self) -> bool {
+        matches!(
+            self.here,
+            RememberedRoom::Pool
+                | RememberedRoom::Chest
+                | RememberedRoom::Book
+                | RememberedRoom::CrystalOrb
+                | RememberedRoom::Monster(_)
+        )
+    }
+
+    /// Return how the monster in the player's room looks, if the player can
+    /// see one
+    pub fn monster_sighting(&self) -> Option<MonsterSighting> {
+        self.sighting
+    }
+
+    /// True if the player is blind
+    pub fn is_blind(&self) -> bool {
+        self.blind
+    }
+
+    /// Return x dimension
+    pub fn xsize(&self) -> u32 {
+        self.size.0
+    }
+
+    /// Return y dimension
+    pub fn ysize(&self) -> u32 {
+        self.size.1
+    }
+
+    /// Return z dimension
+    pub fn zsize(&self) -> u32 {
+        self.size.2
+    }
+}
@@ -8,31 +8,29 @@ pub enum WeaponType {
 
 pub struct Weapon {
     weapon_type: WeaponType,
+    health: u32,
+    bonus: u32, // Enchantment, added to damage and to hit
 }
 
 impl Weapon {
     pub fn new(w: WeaponType) -> Weapon {
-        Weapon { weapon_type: w }
-    }
-
-    pub fn cost(w: WeaponType, is_vendor: bool) -> u32 {
-        if is_vendor {
-            match w {
-                WeaponType::None => 0,
-                WeaponType::Dagger => 1250,
-                WeaponType::Mace => 1500,
-                WeaponType::Sword => 2000,
-            }
-        } else {
-            match w {
-                WeaponType::None => 0,
-                WeaponType::Dagger => 10,
-                WeaponType::Mace => 20,
-                WeaponType::Sword => 30,
-            }
+        Weapon::enchanted(w, 0)
+    }
+
+    /// Create a +N weapon, which never wears out or breaks
+    pub fn enchanted(w: WeaponType, bonus: u32) -> Weapon {
+        Weapon {
+            weapon_type: w,
+            health: Weapon::max_health_by_type(w),
+            bonus,
         }
     }
 
+    /// Return the number of hits a new weapon of a given type can take
+    fn max_health_by_type(w: WeaponType) -> u32 {
+        Weapon::get_enum_value(w) * 7
+    }
+
     pub fn damage_by_type(w: WeaponType) -> u32 {
         match w {
             WeaponType::None => 0,
@@ -53,10 +51,109 @@ impl Weapon {
     }
 
     pub fn damage(&self) -> u32 {
-        Weapon::damage_by_type(self.weapon_type)
+        Weapon::damage_by_type(self.weapon_type) + self.bonus
+    }
+
+    /// Return the weapon's enchantment, 0 for a plain weapon
+    pub fn bonus(&self) -> u32 {
+        self.bonus
+    }
+
+    /// True if the weapon is enchanted
+    pub fn is_enchanted(&self) -> bool {
+        self.bonus > 0
     }
 
     pub fn weapon_type(&self) -> WeaponType {
         self.weapon_type
     }
+
+    /// Wear the weapon down
+    ///
+    /// Return true if the weapon breaks
+    pub fn wear_down(&mut self, amount: u32) -> bool {
+        if self.is_enchanted() {
+            return false;
+        }
+
+        if amount >= self.health {
+            self.health = 0;
+            return true;
+        }
+
+        self.health -= amount;
+
+        false
+    }
+
+    /// Return how much wear the weapon has taken
+    pub fn wear(&self) -> u32 {
+        Weapon::max_health_by_type(self.weapon_type) - self.health
+    }
+
+    /// Return the condition of the weapon as a percentage, or None if it
+    /// doesn't wear
+    pub fn condition(&self) -> Option<u32> {
+        let max_health = Weapon::max_health_by_type(self.weapon_type);
+
+        if max_health == 0 || self.is_enchanted() {
+            return None;
+        }
+
+        Some(self.health * 100 / max_health)
+    }
+
+    /// Restore the weapon to new
+    pub fn repair(&mut self) {
+        self.health = Weapon::max_health_by_type(self.weapon_type);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wears_down_and_breaks() {
+        let mut dagger = Weapon::new(WeaponType::Dagger);
+
+        assert_eq!(dagger.condition(), Some(100));
+
+        for _ in 0..6 {
+            assert!(!dagger.wear_down(1));
+        }
+
+        assert_eq!(dagger.wear(), 6);
+        assert_eq!(dagger.condition(), Some(14));
+        assert!(dagger.wear_down(1));
+        assert_eq!(dagger.condition(), Some(0));
+    }
+
+    #[test]
+    fn better_weapons_last_longer() {
+        let mut sword = Weapon::new(WeaponType::Sword);
+
+        assert!(!sword.wear_down(20));
+        assert!(sword.wear_down(1));
+    }
+
+    #[test]
+    fn repair_restores_a_weapon() {
+        let mut mace = Weapon::new(WeaponType::Mace);
+
+        mace.wear_down(10);
+        mace.repair();
+
+        assert_eq!(mace.wear(), 0);
+        assert_eq!(mace.condition(), Some(100));
+    }
+
+    #[test]
+    fn enchanted_weapons_never_wear() {
+        let mut dagger = Weapon::enchanted(WeaponType::Dagger, 1);
+
+        assert!(!dagger.wear_down(100));
+        assert_eq!(dagger.wear(), 0);
+        assert_eq!(dagger.condition(), None);
+    }
 }